@@ -1,5 +1,5 @@
 pub struct EngineSchematic {
-    part_numbers: Vec<u64>,
+    part_numbers: Vec<SchematicNumber>,
     gear_ratios: Vec<u64>,
 }
 
@@ -10,16 +10,39 @@ struct SchematicSymbol {
     column: usize,
 }
 
-#[derive(Debug)]
-struct SchematicNumber {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicNumber {
     number: u64,
     row: usize,
-    column: usize,
+    start_column: usize,
+    end_column: usize,
+}
+
+impl SchematicNumber {
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn start_column(&self) -> usize {
+        self.start_column
+    }
+
+    pub fn end_column(&self) -> usize {
+        self.end_column
+    }
+
+    pub fn width(&self) -> usize {
+        self.end_column - self.start_column + 1
+    }
 }
 
 impl EngineSchematic {
     pub fn parse(schematic: &str) -> Self {
-        let mut part_numbers: Vec<u64> = Vec::new();
+        let mut part_numbers: Vec<SchematicNumber> = Vec::new();
         let mut gear_ratios: Vec<u64> = Vec::new();
         let schematic_symbols = EngineSchematic::extract_symbol_locations(schematic);
         let schematic_numbers = EngineSchematic::extract_numbers_with_locations(schematic);
@@ -28,7 +51,7 @@ impl EngineSchematic {
             if schematic_symbols.iter().any(|schematic_symbol| {
                 EngineSchematic::is_part_number(schematic_symbol, schematic_number)
             }) {
-                part_numbers.push(schematic_number.number);
+                part_numbers.push(schematic_number.clone());
             }
         }
 
@@ -57,8 +80,15 @@ impl EngineSchematic {
         }
     }
 
+    pub fn part_numbers(&self) -> &[SchematicNumber] {
+        &self.part_numbers
+    }
+
     pub fn part_number_sum(&self) -> u64 {
-        self.part_numbers.iter().sum()
+        self.part_numbers
+            .iter()
+            .map(|part_number| part_number.number)
+            .sum()
     }
 
     pub fn gear_ratio_sum(&self) -> u64 {
@@ -86,69 +116,74 @@ impl EngineSchematic {
 
     fn extract_numbers_with_locations(schematic: &str) -> Vec<SchematicNumber> {
         let mut schematic_numbers: Vec<SchematicNumber> = Vec::new();
-        let mut number_digits: Vec<char> = Vec::new();
-        let mut number_location: (usize, usize) = (0, 0);
 
         for (line_index, schematic_line) in schematic.split('\n').enumerate() {
+            let mut number_digits: Vec<char> = Vec::new();
+            let mut start_column: usize = 0;
+
             for (character_index, character) in schematic_line.chars().enumerate() {
                 if character.is_ascii_digit() {
                     if number_digits.is_empty() {
-                        number_location = (line_index, character_index);
+                        start_column = character_index;
                     }
 
                     number_digits.push(character);
                 } else if !number_digits.is_empty() {
-                    let number: u64 = number_digits
-                        .iter()
-                        .cloned()
-                        .collect::<String>()
-                        .parse()
-                        .unwrap();
-
-                    schematic_numbers.push(SchematicNumber {
-                        number,
-                        row: number_location.0,
-                        column: number_location.1,
-                    });
-                    number_digits.resize(0, '0');
+                    schematic_numbers.push(EngineSchematic::create_schematic_number(
+                        &number_digits,
+                        line_index,
+                        start_column,
+                    ));
+                    number_digits.clear();
                 }
             }
 
             if !number_digits.is_empty() {
-                let number: u64 = number_digits
-                    .iter()
-                    .cloned()
-                    .collect::<String>()
-                    .parse()
-                    .unwrap();
-
-                schematic_numbers.push(SchematicNumber {
-                    number,
-                    row: number_location.0,
-                    column: number_location.1,
-                });
-                number_digits.resize(0, '0');
+                schematic_numbers.push(EngineSchematic::create_schematic_number(
+                    &number_digits,
+                    line_index,
+                    start_column,
+                ));
             }
         }
 
         schematic_numbers
     }
 
+    fn create_schematic_number(
+        number_digits: &[char],
+        row: usize,
+        start_column: usize,
+    ) -> SchematicNumber {
+        let number: u64 = number_digits
+            .iter()
+            .cloned()
+            .collect::<String>()
+            .parse()
+            .unwrap();
+
+        SchematicNumber {
+            number,
+            row,
+            start_column,
+            end_column: start_column + number_digits.len() - 1,
+        }
+    }
+
     fn is_part_number(
         schematic_symbol: &SchematicSymbol,
         schematic_number: &SchematicNumber,
     ) -> bool {
-        let number_last_digit_index = schematic_number.number.to_string().len() - 1;
-
         let is_number_in_adjacent_or_same_row_as_symbol =
             (schematic_symbol.row as isize - schematic_number.row as isize).abs() <= 1;
 
-        let is_number_in_same_column_as_symbol = schematic_symbol.column >= schematic_number.column
-            && schematic_symbol.column <= schematic_number.column + number_last_digit_index;
+        let is_number_in_same_column_as_symbol = schematic_symbol.column
+            >= schematic_number.start_column
+            && schematic_symbol.column <= schematic_number.end_column;
 
         let is_number_in_adjacent_column_to_symbol = schematic_symbol.column + 1
-            == schematic_number.column
-            || schematic_symbol.column == schematic_number.column + number_last_digit_index + 1;
+            == schematic_number.start_column
+            || schematic_symbol.column == schematic_number.end_column + 1;
 
         is_number_in_adjacent_or_same_row_as_symbol
             && (is_number_in_same_column_as_symbol || is_number_in_adjacent_column_to_symbol)
@@ -235,6 +270,28 @@ mod tests {
         assert_part_number_sum(4361, "467..114..\n...*......\n..35..633.\n......#...\n617*......\n.....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..\n");
     }
 
+    #[test]
+    fn engine_schematic_numbers_with_leading_zeros() {
+        assert_part_number_sum(7, "007*");
+        assert_part_number_sum(7, "..*\n007");
+        assert_part_number_sum(0, "....*\n007..");
+        assert_part_number_sum(0, "007.*");
+        assert_gear_ration_sum(14, "007*02");
+    }
+
+    #[test]
+    fn engine_schematic_part_number_spans() {
+        let schematic = EngineSchematic::parse("..*.\n.007\n12..");
+        let part_numbers = schematic.part_numbers();
+
+        assert_eq!(1, part_numbers.len());
+        assert_eq!(7, part_numbers[0].number());
+        assert_eq!(1, part_numbers[0].row());
+        assert_eq!(1, part_numbers[0].start_column());
+        assert_eq!(3, part_numbers[0].end_column());
+        assert_eq!(3, part_numbers[0].width());
+    }
+
     #[test]
     fn engine_schematic_gear_ration() {
         assert_gear_ration_sum(6, "3*2");
@@ -1,2 +1,2 @@
 mod engine_schematic;
-pub use engine_schematic::EngineSchematic;
+pub use engine_schematic::{EngineSchematic, SchematicNumber};
@@ -5,12 +5,47 @@ extern crate day_4;
 use day_4::Scratchcards;
 
 fn main() -> std::io::Result<()> {
+    let print_report = std::env::args().any(|argument| argument == "--report");
     let mut scratchcards_input_file =
         File::open("/home/lpaulic/Documents/github/advent-of-code/2023/day-4/src/bin/data/input")?;
     let mut scratchcards_input = String::new();
 
     scratchcards_input_file.read_to_string(&mut scratchcards_input)?;
-    let scratchcards = Scratchcards::parse(&scratchcards_input);
+
+    let scratchcards = match Scratchcards::parse(&scratchcards_input) {
+        Ok(scratchcards) => scratchcards,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                eprintln!("{}", diagnostic);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    if print_report {
+        println!(
+            "{:>6} | {:<40} | {:>6} | {:>10} | {:>11}",
+            "Card", "Matching numbers", "Points", "Copies won", "Copies held"
+        );
+        for card_report in scratchcards.report() {
+            let matching_numbers = card_report
+                .get_matching_numbers()
+                .iter()
+                .map(|number| number.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            println!(
+                "{:>6} | {:<40} | {:>6} | {:>10} | {:>11}",
+                card_report.get_id(),
+                matching_numbers,
+                card_report.get_points(),
+                card_report.get_copies_won(),
+                card_report.get_copies_held()
+            );
+        }
+    }
+
     println!("Total scratchcard points: {}", scratchcards.points_sum());
     println!(
         "Number of won scratch cards: {}",
@@ -1,2 +1,9 @@
+mod rules;
 mod scratchcard;
-pub use scratchcard::Scratchcards;
+mod validation;
+pub use rules::{
+    CappedCopiesReward, DoublingPoints, FibonacciPoints, LinearPoints, NextCardsReward,
+    OffsetCardsReward, RewardRule, RewardRuleError, ScoringRule,
+};
+pub use scratchcard::{ScratchcardReport, Scratchcards};
+pub use validation::ScratchcardDiagnostic;
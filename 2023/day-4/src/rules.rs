@@ -0,0 +1,193 @@
+use std::fmt;
+
+pub trait ScoringRule {
+    fn points(&self, matching_numbers_count: u64) -> u64;
+}
+
+pub trait RewardRule {
+    // Offset of the first won card relative to the winning card and the
+    // number of consecutive cards won from there.
+    fn won_cards(&self, matching_numbers_count: u64) -> (usize, usize);
+
+    fn max_copies(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardRuleError {
+    ZeroOffset,
+    ZeroMaxCopies,
+}
+
+impl fmt::Display for RewardRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewardRuleError::ZeroOffset => {
+                write!(
+                    f,
+                    "won cards have to start at least one card after the winning card"
+                )
+            }
+            RewardRuleError::ZeroMaxCopies => {
+                write!(f, "every card has to be allowed at least its original copy")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DoublingPoints;
+
+impl ScoringRule for DoublingPoints {
+    fn points(&self, matching_numbers_count: u64) -> u64 {
+        if matching_numbers_count == 0 {
+            0
+        } else {
+            2_u64.pow(matching_numbers_count as u32 - 1)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinearPoints;
+
+impl ScoringRule for LinearPoints {
+    fn points(&self, matching_numbers_count: u64) -> u64 {
+        matching_numbers_count
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FibonacciPoints;
+
+impl ScoringRule for FibonacciPoints {
+    fn points(&self, matching_numbers_count: u64) -> u64 {
+        let (mut current, mut next) = (0_u64, 1_u64);
+
+        for _ in 0..matching_numbers_count {
+            (current, next) = (next, current + next);
+        }
+
+        current
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NextCardsReward;
+
+impl RewardRule for NextCardsReward {
+    fn won_cards(&self, matching_numbers_count: u64) -> (usize, usize) {
+        (1, matching_numbers_count as usize)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OffsetCardsReward {
+    offset: usize,
+}
+
+impl OffsetCardsReward {
+    // An offset of zero would make a card win copies of itself.
+    pub fn new(offset: usize) -> Result<Self, RewardRuleError> {
+        if offset == 0 {
+            return Err(RewardRuleError::ZeroOffset);
+        }
+
+        Ok(OffsetCardsReward { offset })
+    }
+}
+
+impl RewardRule for OffsetCardsReward {
+    fn won_cards(&self, matching_numbers_count: u64) -> (usize, usize) {
+        (self.offset, matching_numbers_count as usize)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CappedCopiesReward<R: RewardRule> {
+    reward_rule: R,
+    max_copies: u64,
+}
+
+impl<R: RewardRule> CappedCopiesReward<R> {
+    // The cap includes the original card, so it has to be at least one.
+    pub fn new(reward_rule: R, max_copies: u64) -> Result<Self, RewardRuleError> {
+        if max_copies == 0 {
+            return Err(RewardRuleError::ZeroMaxCopies);
+        }
+
+        Ok(CappedCopiesReward {
+            reward_rule,
+            max_copies,
+        })
+    }
+}
+
+impl<R: RewardRule> RewardRule for CappedCopiesReward<R> {
+    fn won_cards(&self, matching_numbers_count: u64) -> (usize, usize) {
+        self.reward_rule.won_cards(matching_numbers_count)
+    }
+
+    fn max_copies(&self) -> Option<u64> {
+        Some(
+            self.reward_rule
+                .max_copies()
+                .map_or(self.max_copies, |max_copies| {
+                    max_copies.min(self.max_copies)
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubling_points() {
+        let points: Vec<u64> = (0..6).map(|count| DoublingPoints.points(count)).collect();
+        assert_eq!(vec![0, 1, 2, 4, 8, 16], points);
+    }
+
+    #[test]
+    fn linear_points() {
+        let points: Vec<u64> = (0..6).map(|count| LinearPoints.points(count)).collect();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], points);
+    }
+
+    #[test]
+    fn fibonacci_points() {
+        let points: Vec<u64> = (0..8).map(|count| FibonacciPoints.points(count)).collect();
+        assert_eq!(vec![0, 1, 1, 2, 3, 5, 8, 13], points);
+    }
+
+    #[test]
+    fn capped_copies_reward_keeps_smallest_cap() {
+        let reward_rule =
+            CappedCopiesReward::new(CappedCopiesReward::new(NextCardsReward, 3).unwrap(), 5)
+                .unwrap();
+
+        assert_eq!((1, 4), reward_rule.won_cards(4));
+        assert_eq!(Some(3), reward_rule.max_copies());
+    }
+
+    #[test]
+    fn reward_rules_reject_zero_offset_and_cap() {
+        assert_eq!(
+            RewardRuleError::ZeroOffset,
+            OffsetCardsReward::new(0).unwrap_err()
+        );
+        assert_eq!(
+            RewardRuleError::ZeroMaxCopies,
+            CappedCopiesReward::new(NextCardsReward, 0).unwrap_err()
+        );
+        assert_eq!((1, 2), OffsetCardsReward::new(1).unwrap().won_cards(2));
+        assert_eq!(
+            Some(1),
+            CappedCopiesReward::new(NextCardsReward, 1)
+                .unwrap()
+                .max_copies()
+        );
+    }
+}
@@ -1,34 +1,82 @@
+use std::collections::HashSet;
+
+use crate::rules::{DoublingPoints, NextCardsReward, RewardRule, ScoringRule};
+use crate::validation::{self, ScratchcardDiagnostic};
+
 #[derive(Debug)]
 pub struct Scratchcards {
     cards: Vec<Scratchcard>,
 }
 
 impl Scratchcards {
-    pub fn parse(scratch_cards: &str) -> Self {
+    pub fn parse(scratch_cards: &str) -> Result<Self, Vec<ScratchcardDiagnostic>> {
+        Scratchcards::parse_with_rules(scratch_cards, &DoublingPoints, &NextCardsReward)
+    }
+
+    // Copies are only propagated over validated cards, which are in order and
+    // numbered from one without gaps, so a card index follows from its id.
+    pub fn parse_with_rules(
+        scratch_cards: &str,
+        scoring_rule: &impl ScoringRule,
+        reward_rule: &impl RewardRule,
+    ) -> Result<Self, Vec<ScratchcardDiagnostic>> {
+        let diagnostics = validation::validate(scratch_cards);
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+
         let mut cards: Vec<Scratchcard> = Vec::new();
 
-        for scratch_card in scratch_cards.trim().split('\n') {
-            cards.push(Scratchcard::parse(scratch_card));
+        for scratch_card in scratch_cards
+            .split('\n')
+            .filter(|scratch_card| !scratch_card.trim().is_empty())
+        {
+            let mut card = Scratchcard::parse(scratch_card);
+            card.points = scoring_rule.points(card.get_correct_guess_count());
+            cards.push(card);
         }
 
-        for i in 1..=cards.len() {
-            let guessed_numbers_count = cards[i - 1].get_correct_guess_count() as usize;
-            for j in 1..=cards.len() {
-                if cards[j - 1].id > cards[i - 1].id
-                    && cards[j - 1].id <= cards[i + guessed_numbers_count - 1].id
-                {
-                    cards[j - 1].number_of_copies += cards[i - 1].number_of_copies;
-                }
+        Scratchcards::propagate_copies(&mut cards, reward_rule);
+
+        Ok(Scratchcards { cards })
+    }
+
+    fn propagate_copies(cards: &mut [Scratchcard], reward_rule: &impl RewardRule) {
+        let mut copies_starting_at: Vec<u64> = vec![0; cards.len() + 1];
+        let mut copies_expiring_at: Vec<u64> = vec![0; cards.len() + 1];
+        let mut won_copies: u64 = 0;
+
+        for card_index in 0..cards.len() {
+            won_copies += copies_starting_at[card_index];
+            won_copies -= copies_expiring_at[card_index];
+            cards[card_index].number_of_copies += won_copies;
+
+            // The original card is always held, whatever the cap.
+            if let Some(max_copies) = reward_rule.max_copies() {
+                cards[card_index].number_of_copies =
+                    cards[card_index].number_of_copies.min(max_copies.max(1));
+            }
+
+            let (won_cards_offset, won_cards_count) =
+                reward_rule.won_cards(cards[card_index].get_correct_guess_count());
+            let first_won_card_index = (card_index + won_cards_offset).min(cards.len());
+            let last_won_card_index = (first_won_card_index + won_cards_count).min(cards.len());
+
+            // Cards at or before the winning card were already counted, so
+            // only forward rewards can be propagated.
+            if won_cards_offset > 0 && first_won_card_index < last_won_card_index {
+                copies_starting_at[first_won_card_index] += cards[card_index].number_of_copies;
+                copies_expiring_at[last_won_card_index] += cards[card_index].number_of_copies;
             }
         }
+    }
 
-        Scratchcards { cards }
+    pub fn validate(scratch_cards: &str) -> Vec<ScratchcardDiagnostic> {
+        validation::validate(scratch_cards)
     }
 
     pub fn points_sum(&self) -> u64 {
-        self.cards
-            .iter()
-            .fold(0, |acc, card| acc + card.get_points())
+        self.cards.iter().fold(0, |acc, card| acc + card.points)
     }
 
     pub fn won_cards_count(&self) -> u64 {
@@ -36,97 +84,122 @@ impl Scratchcards {
             .iter()
             .fold(0, |acc, card| acc + card.number_of_copies)
     }
+
+    pub fn report(&self) -> Vec<ScratchcardReport> {
+        self.cards
+            .iter()
+            .map(|card| ScratchcardReport {
+                id: card.id,
+                matching_numbers: card.matching_numbers.clone(),
+                points: card.points,
+                copies_won: card.number_of_copies - 1,
+                copies_held: card.number_of_copies,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScratchcardReport {
+    id: u64,
+    matching_numbers: Vec<u64>,
+    points: u64,
+    copies_won: u64,
+    copies_held: u64,
+}
+
+impl ScratchcardReport {
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_matching_numbers(&self) -> &[u64] {
+        &self.matching_numbers
+    }
+
+    pub fn get_points(&self) -> u64 {
+        self.points
+    }
+
+    pub fn get_copies_won(&self) -> u64 {
+        self.copies_won
+    }
+
+    pub fn get_copies_held(&self) -> u64 {
+        self.copies_held
+    }
 }
 
 #[derive(Clone, Debug)]
-struct Scratchcard {
+pub(crate) struct Scratchcard {
     id: u64,
-    winning_numbers: Vec<u64>,
-    scratched_numbers: Vec<u64>,
+    matching_numbers: Vec<u64>,
+    points: u64,
     number_of_copies: u64,
 }
 
 impl Scratchcard {
     fn parse(scratch_card: &str) -> Self {
-        let mut winning_numbers: Vec<u64> = Vec::new();
-        let mut scratched_numbers: Vec<u64> = Vec::new();
-
-        let mut card_line = scratch_card.split(':');
-
-        let card_id_string = card_line.next().unwrap();
-        let card_id_split_index = card_id_string.chars().fold(0, |mut index, char| {
-            if char.is_whitespace() {
-                index = card_id_string.rfind(char).unwrap();
-            }
-            index
-        });
-        let (_, card_id) = card_id_string.split_at(card_id_split_index);
+        let (card_id_string, card_numbers) = scratch_card.split_once(':').unwrap();
+        let (winning_numbers_string, scratched_numbers_string) =
+            card_numbers.split_once('|').unwrap();
 
-        let card_numbers = card_line.next().unwrap();
-        let mut numbers = card_numbers.split('|');
+        let winning_numbers: HashSet<u64> = winning_numbers_string
+            .split_whitespace()
+            .map(|str| str.parse().unwrap())
+            .collect();
 
-        let winning_numbers_string = numbers.next().unwrap();
-        let scratched_numbers_string = numbers.next().unwrap();
+        let scratched_numbers: HashSet<u64> = scratched_numbers_string
+            .split_whitespace()
+            .map(|str| str.parse().unwrap())
+            .collect();
 
-        winning_numbers.append(
-            &mut winning_numbers_string
-                .split_whitespace()
-                .map(|str| str.parse().unwrap())
-                .collect::<Vec<u64>>(),
-        );
-
-        scratched_numbers.append(
-            &mut scratched_numbers_string
-                .split_whitespace()
-                .map(|str| str.trim().parse().unwrap())
-                .collect::<Vec<u64>>(),
-        );
+        let mut matching_numbers: Vec<u64> = winning_numbers
+            .intersection(&scratched_numbers)
+            .cloned()
+            .collect();
+        matching_numbers.sort_unstable();
 
         Scratchcard {
-            id: card_id.trim().parse::<u64>().unwrap(),
-            winning_numbers,
-            scratched_numbers,
+            id: Scratchcard::parse_id(card_id_string).unwrap(),
+            matching_numbers,
+            points: 0,
             number_of_copies: 1,
         }
     }
 
-    fn get_points(&self) -> u64 {
-        let number_of_matches = self
-            .winning_numbers
-            .iter()
-            .filter(|winning_number| self.scratched_numbers.contains(winning_number))
-            .count();
-
-        if number_of_matches == 0 {
-            0
-        } else {
-            2_u64.pow(number_of_matches as u32 - 1)
-        }
+    pub(crate) fn parse_id(card_id_string: &str) -> Option<u64> {
+        card_id_string
+            .trim()
+            .strip_prefix("Card")?
+            .trim()
+            .parse::<u64>()
+            .ok()
     }
 
     pub fn get_correct_guess_count(&self) -> u64 {
-        self.winning_numbers
-            .iter()
-            .filter(|winning_number| self.scratched_numbers.contains(winning_number))
-            .count() as u64
+        self.matching_numbers.len() as u64
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::{CappedCopiesReward, FibonacciPoints, LinearPoints, OffsetCardsReward};
 
     fn assert_scratchcard_points_sum(expected_scratchcard_points_sum: u64, scratchcard_game: &str) {
         assert_eq!(
             expected_scratchcard_points_sum,
-            Scratchcards::parse(scratchcard_game).points_sum()
+            Scratchcards::parse(scratchcard_game).unwrap().points_sum()
         );
     }
 
     fn assert_scratchcard_card_sum(expected_scratchcard_card_sum: u64, scratchcard_game: &str) {
         assert_eq!(
             expected_scratchcard_card_sum,
-            Scratchcards::parse(scratchcard_game).won_cards_count()
+            Scratchcards::parse(scratchcard_game)
+                .unwrap()
+                .won_cards_count()
         );
     }
 
@@ -137,44 +210,44 @@ mod tests {
 
     #[test]
     fn one_scratchcard_one_winning_number() {
-        assert_scratchcard_points_sum(1, "Card 2: 1 2 3 4 5 | 1 12 13 14 15 16 17 18");
+        assert_scratchcard_points_sum(1, "Card 1: 1 2 3 4 5 | 1 12 13 14 15 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_two_winning_numbers() {
-        assert_scratchcard_points_sum(2, "Card 3: 1 2 3 4 5 | 1 2 13 14 15 16 17 18");
+        assert_scratchcard_points_sum(2, "Card 1: 1 2 3 4 5 | 1 2 13 14 15 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_three_winning_numbers() {
-        assert_scratchcard_points_sum(4, "Card 4: 1 2 3 4 5 | 1 2 3 14 15 16 17 18");
+        assert_scratchcard_points_sum(4, "Card 1: 1 2 3 4 5 | 1 2 3 14 15 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_four_winning_numbers() {
-        assert_scratchcard_points_sum(8, "Card 5: 1 2 3 4 5 | 1 2 3 4 15 16 17 18");
+        assert_scratchcard_points_sum(8, "Card 1: 1 2 3 4 5 | 1 2 3 4 15 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_five_winning_numbers() {
-        assert_scratchcard_points_sum(16, "Card 6: 1 2 3 4 5 | 1 2 3 4 5 16 17 18");
+        assert_scratchcard_points_sum(16, "Card 1: 1 2 3 4 5 | 1 2 3 4 5 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_five_winning_numbers_multiple_spaces_before_card_id() {
-        assert_scratchcard_points_sum(16, "Card   6: 1 2 3 4 5 | 1 2 3 4 5 16 17 18");
+        assert_scratchcard_points_sum(16, "Card   1: 1 2 3 4 5 | 1 2 3 4 5 16 17 18");
     }
 
     #[test]
     fn one_scratchcard_five_winning_numbers_multiple_spaces_number() {
-        assert_scratchcard_points_sum(16, "Card 6: 1 2 3 4  5 | 1 2   3 4 5 16 17 18");
+        assert_scratchcard_points_sum(16, "Card 1: 1 2 3 4  5 | 1 2   3 4 5 16 17 18");
     }
 
     #[test]
     fn multi_scratchcards_four_winning_numbers() {
         assert_scratchcard_points_sum(
             4,
-            "Card 1: 11 12 3 4 15 | 1 2 3 4 5 16 17 18\nCard 1: 1 12 13 14 5 | 1 2 3 4 5 16 17 18",
+            "Card 1: 11 12 3 4 15 | 1 2 3 4 5 16 17 18\nCard 2: 1 12 13 14 5 | 1 2 3 4 5 16 17 18",
         );
     }
 
@@ -182,4 +255,147 @@ mod tests {
     fn multi_scratchcards_thirty_won_cards() {
         assert_scratchcard_card_sum(30, "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\nCard 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\nCard 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\nCard 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\nCard 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11")
     }
+
+    #[test]
+    fn multi_scratchcards_wins_past_last_card() {
+        assert_scratchcard_card_sum(1, "Card 1: 1 2 3 | 1 2 3");
+        assert_scratchcard_card_sum(
+            7,
+            "Card 1: 1 2 3 | 1 2 3\nCard 2: 1 2 3 | 1 2 3\nCard 3: 1 2 3 | 4 5 6",
+        );
+    }
+
+    #[test]
+    fn multi_scratchcards_report() {
+        let report = Scratchcards::parse(
+            "Card 1: 1 2 3 | 3 2 9\nCard 2: 4 5 6 | 6 7 8\nCard 3: 1 2 3 | 4 5 6",
+        )
+        .unwrap()
+        .report();
+
+        assert_eq!(3, report.len());
+
+        assert_eq!(1, report[0].get_id());
+        assert_eq!(&[2, 3], report[0].get_matching_numbers());
+        assert_eq!(2, report[0].get_points());
+        assert_eq!(0, report[0].get_copies_won());
+        assert_eq!(1, report[0].get_copies_held());
+
+        assert_eq!(2, report[1].get_id());
+        assert_eq!(&[6], report[1].get_matching_numbers());
+        assert_eq!(1, report[1].get_points());
+        assert_eq!(1, report[1].get_copies_won());
+        assert_eq!(2, report[1].get_copies_held());
+
+        assert_eq!(3, report[2].get_id());
+        assert!(report[2].get_matching_numbers().is_empty());
+        assert_eq!(0, report[2].get_points());
+        assert_eq!(3, report[2].get_copies_won());
+        assert_eq!(4, report[2].get_copies_held());
+    }
+
+    #[test]
+    fn multi_scratchcards_alternative_rules() {
+        let scratchcard_game = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\nCard 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\nCard 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\nCard 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\nCard 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+        let scratchcards =
+            Scratchcards::parse_with_rules(scratchcard_game, &DoublingPoints, &NextCardsReward)
+                .unwrap();
+        assert_eq!(13, scratchcards.points_sum());
+        assert_eq!(30, scratchcards.won_cards_count());
+
+        let scratchcards =
+            Scratchcards::parse_with_rules(scratchcard_game, &LinearPoints, &NextCardsReward)
+                .unwrap();
+        assert_eq!(9, scratchcards.points_sum());
+
+        let scratchcards =
+            Scratchcards::parse_with_rules(scratchcard_game, &FibonacciPoints, &NextCardsReward)
+                .unwrap();
+        assert_eq!(6, scratchcards.points_sum());
+
+        let scratchcards = Scratchcards::parse_with_rules(
+            scratchcard_game,
+            &DoublingPoints,
+            &OffsetCardsReward::new(2).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(19, scratchcards.won_cards_count());
+
+        let scratchcards = Scratchcards::parse_with_rules(
+            scratchcard_game,
+            &DoublingPoints,
+            &CappedCopiesReward::new(NextCardsReward, 4).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(16, scratchcards.won_cards_count());
+
+        let scratchcards = Scratchcards::parse_with_rules(
+            scratchcard_game,
+            &DoublingPoints,
+            &CappedCopiesReward::new(NextCardsReward, 1).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(6, scratchcards.won_cards_count());
+        assert!(scratchcards
+            .report()
+            .iter()
+            .all(|card_report| card_report.get_copies_won() == 0));
+    }
+
+    #[test]
+    fn multi_scratchcards_out_of_order() {
+        assert_eq!(
+            vec![ScratchcardDiagnostic::OutOfOrderCard {
+                line: 2,
+                card_id: 1,
+                previous_card_id: 3
+            }],
+            Scratchcards::parse(
+                "Card 3: 1 2 3 | 4 5 6\nCard 1: 1 2 3 | 1 2 3\nCard 2: 1 2 3 | 1 2 3"
+            )
+            .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn multi_scratchcards_invalid_cards_are_rejected() {
+        // Card 1 would otherwise win a copy of card 5, the card after it.
+        assert_eq!(
+            vec![ScratchcardDiagnostic::MissingCardIds {
+                first_card_id: 2,
+                last_card_id: 4
+            }],
+            Scratchcards::parse("Card 1: 1 | 1\nCard 5: 2 | 3").unwrap_err()
+        );
+        assert_eq!(
+            vec![ScratchcardDiagnostic::MalformedCard { line: 2 }],
+            Scratchcards::parse("Card 1: 1 | 1\nCard 2 2 3").unwrap_err()
+        );
+        assert_eq!(
+            2,
+            Scratchcards::parse("Card 1: 1 | 2\n\nCard 2: 1 | 2\n")
+                .unwrap()
+                .won_cards_count()
+        );
+    }
+
+    #[test]
+    fn multi_scratchcards_generated_hundred_thousand_cards() {
+        const CARDS_COUNT: u64 = 100_000;
+        let scratchcard_game = (1..=CARDS_COUNT)
+            .map(|card_id| {
+                if card_id % 5 == 0 {
+                    format!("Card {}: 1 2 3 4 5 | 1 2 3 96 97 98 99", card_id)
+                } else {
+                    format!("Card {}: 1 2 3 4 5 | 10 20 30 96 97 98 99", card_id)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        // Every fifth card wins the next three cards, except the last one which
+        // has no cards left to win.
+        assert_scratchcard_card_sum(CARDS_COUNT + 3 * (CARDS_COUNT / 5 - 1), &scratchcard_game);
+    }
 }
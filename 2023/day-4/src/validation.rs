@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::scratchcard::Scratchcard;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScratchcardDiagnostic {
+    MalformedCard {
+        line: usize,
+    },
+    InvalidCardId {
+        line: usize,
+        card_id: String,
+    },
+    InvalidNumber {
+        line: usize,
+        card_id: u64,
+        number: String,
+    },
+    DuplicateCardId {
+        line: usize,
+        card_id: u64,
+        first_line: usize,
+    },
+    OutOfOrderCard {
+        line: usize,
+        card_id: u64,
+        previous_card_id: u64,
+    },
+    MissingCardIds {
+        first_card_id: u64,
+        last_card_id: u64,
+    },
+    DuplicateWinningNumber {
+        line: usize,
+        card_id: u64,
+        number: u64,
+    },
+    MismatchedColumnCount {
+        line: usize,
+        card_id: u64,
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+impl fmt::Display for ScratchcardDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScratchcardDiagnostic::MalformedCard { line } => {
+                write!(
+                    f,
+                    "line {}: expected `Card <id>: <numbers> | <numbers>`",
+                    line
+                )
+            }
+            ScratchcardDiagnostic::InvalidCardId { line, card_id } => {
+                write!(f, "line {}: invalid card id `{}`", line, card_id)
+            }
+            ScratchcardDiagnostic::InvalidNumber {
+                line,
+                card_id,
+                number,
+            } => write!(
+                f,
+                "line {}: card {} contains invalid number `{}`",
+                line, card_id, number
+            ),
+            ScratchcardDiagnostic::DuplicateCardId {
+                line,
+                card_id,
+                first_line,
+            } => write!(
+                f,
+                "line {}: card {} was already defined on line {}",
+                line, card_id, first_line
+            ),
+            ScratchcardDiagnostic::OutOfOrderCard {
+                line,
+                card_id,
+                previous_card_id,
+            } => write!(
+                f,
+                "line {}: card {} follows card {}",
+                line, card_id, previous_card_id
+            ),
+            ScratchcardDiagnostic::MissingCardIds {
+                first_card_id,
+                last_card_id,
+            } => {
+                if first_card_id == last_card_id {
+                    write!(f, "card {} is missing", first_card_id)
+                } else {
+                    write!(f, "cards {} to {} are missing", first_card_id, last_card_id)
+                }
+            }
+            ScratchcardDiagnostic::DuplicateWinningNumber {
+                line,
+                card_id,
+                number,
+            } => write!(
+                f,
+                "line {}: card {} lists winning number {} more than once",
+                line, card_id, number
+            ),
+            ScratchcardDiagnostic::MismatchedColumnCount {
+                line,
+                card_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: card {} has {} winning and {} scratched numbers, expected {} and {}",
+                line, card_id, found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+pub fn validate(scratch_cards: &str) -> Vec<ScratchcardDiagnostic> {
+    let mut diagnostics: Vec<ScratchcardDiagnostic> = Vec::new();
+    let mut card_id_lines: HashMap<u64, usize> = HashMap::new();
+    let mut previous_card_id: Option<u64> = None;
+    let mut expected_column_count: Option<(usize, usize)> = None;
+
+    for (line_index, scratch_card) in scratch_cards.split('\n').enumerate() {
+        let line = line_index + 1;
+
+        if scratch_card.trim().is_empty() {
+            continue;
+        }
+
+        let Some((card_id_string, card_numbers)) = scratch_card.split_once(':') else {
+            diagnostics.push(ScratchcardDiagnostic::MalformedCard { line });
+            continue;
+        };
+
+        let Some((winning_numbers_string, scratched_numbers_string)) = card_numbers.split_once('|')
+        else {
+            diagnostics.push(ScratchcardDiagnostic::MalformedCard { line });
+            continue;
+        };
+
+        let Some(card_id) = Scratchcard::parse_id(card_id_string) else {
+            diagnostics.push(ScratchcardDiagnostic::InvalidCardId {
+                line,
+                card_id: card_id_string.trim().to_string(),
+            });
+            continue;
+        };
+
+        if let Some(first_line) = card_id_lines.get(&card_id) {
+            diagnostics.push(ScratchcardDiagnostic::DuplicateCardId {
+                line,
+                card_id,
+                first_line: *first_line,
+            });
+        } else {
+            card_id_lines.insert(card_id, line);
+        }
+
+        if let Some(previous_card_id) = previous_card_id {
+            if card_id < previous_card_id {
+                diagnostics.push(ScratchcardDiagnostic::OutOfOrderCard {
+                    line,
+                    card_id,
+                    previous_card_id,
+                });
+            }
+        }
+        previous_card_id = Some(card_id);
+
+        let winning_numbers =
+            parse_numbers(winning_numbers_string, line, card_id, &mut diagnostics);
+        let scratched_numbers =
+            parse_numbers(scratched_numbers_string, line, card_id, &mut diagnostics);
+
+        let mut seen_winning_numbers: HashSet<u64> = HashSet::new();
+        let mut reported_winning_numbers: HashSet<u64> = HashSet::new();
+        for winning_number in &winning_numbers {
+            if !seen_winning_numbers.insert(*winning_number)
+                && reported_winning_numbers.insert(*winning_number)
+            {
+                diagnostics.push(ScratchcardDiagnostic::DuplicateWinningNumber {
+                    line,
+                    card_id,
+                    number: *winning_number,
+                });
+            }
+        }
+
+        let column_count = (winning_numbers.len(), scratched_numbers.len());
+        match expected_column_count {
+            Some(expected) if expected != column_count => {
+                diagnostics.push(ScratchcardDiagnostic::MismatchedColumnCount {
+                    line,
+                    card_id,
+                    expected,
+                    found: column_count,
+                });
+            }
+            Some(_) => {}
+            None => expected_column_count = Some(column_count),
+        }
+    }
+
+    let mut card_ids: Vec<u64> = card_id_lines.into_keys().collect();
+    card_ids.sort_unstable();
+
+    let mut expected_card_id = 1;
+    for card_id in card_ids {
+        if card_id > expected_card_id {
+            diagnostics.push(ScratchcardDiagnostic::MissingCardIds {
+                first_card_id: expected_card_id,
+                last_card_id: card_id - 1,
+            });
+        }
+        expected_card_id = card_id + 1;
+    }
+
+    diagnostics
+}
+
+fn parse_numbers(
+    numbers_string: &str,
+    line: usize,
+    card_id: u64,
+    diagnostics: &mut Vec<ScratchcardDiagnostic>,
+) -> Vec<u64> {
+    numbers_string
+        .split_whitespace()
+        .filter_map(|number| match number.parse::<u64>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                diagnostics.push(ScratchcardDiagnostic::InvalidNumber {
+                    line,
+                    card_id,
+                    number: number.to_string(),
+                });
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_scratchcards_have_no_diagnostics() {
+        assert!(validate("Card 1: 1 2 | 1 3 4\nCard   2: 5 6 | 7 8 9\n").is_empty());
+    }
+
+    #[test]
+    fn malformed_cards() {
+        assert_eq!(
+            vec![
+                ScratchcardDiagnostic::MalformedCard { line: 1 },
+                ScratchcardDiagnostic::MalformedCard { line: 2 },
+                ScratchcardDiagnostic::InvalidCardId {
+                    line: 3,
+                    card_id: "Crad 3".to_string()
+                },
+                ScratchcardDiagnostic::InvalidNumber {
+                    line: 4,
+                    card_id: 4,
+                    number: "x".to_string()
+                },
+            ],
+            validate("Card 1 1 2 | 3 4\nCard 2: 1 2 3 4\nCrad 3: 1 2 | 3 4\nCard 4: 1 x | 3 4")
+                .into_iter()
+                .filter(|diagnostic| !matches!(
+                    diagnostic,
+                    ScratchcardDiagnostic::MissingCardIds { .. }
+                        | ScratchcardDiagnostic::MismatchedColumnCount { .. }
+                ))
+                .collect::<Vec<ScratchcardDiagnostic>>()
+        );
+    }
+
+    #[test]
+    fn duplicate_and_out_of_order_card_ids() {
+        assert_eq!(
+            vec![
+                ScratchcardDiagnostic::OutOfOrderCard {
+                    line: 2,
+                    card_id: 1,
+                    previous_card_id: 2
+                },
+                ScratchcardDiagnostic::DuplicateCardId {
+                    line: 3,
+                    card_id: 2,
+                    first_line: 1
+                },
+            ],
+            validate("Card 2: 1 | 2\nCard 1: 1 | 2\nCard 2: 1 | 2")
+        );
+    }
+
+    #[test]
+    fn missing_card_ids() {
+        assert_eq!(
+            vec![
+                ScratchcardDiagnostic::MissingCardIds {
+                    first_card_id: 1,
+                    last_card_id: 1
+                },
+                ScratchcardDiagnostic::MissingCardIds {
+                    first_card_id: 3,
+                    last_card_id: 5
+                },
+            ],
+            validate("Card 2: 1 | 2\nCard 6: 1 | 2")
+        );
+    }
+
+    #[test]
+    fn duplicate_winning_numbers_and_column_counts() {
+        assert_eq!(
+            vec![
+                ScratchcardDiagnostic::DuplicateWinningNumber {
+                    line: 2,
+                    card_id: 2,
+                    number: 7
+                },
+                ScratchcardDiagnostic::MismatchedColumnCount {
+                    line: 2,
+                    card_id: 2,
+                    expected: (2, 3),
+                    found: (3, 3)
+                },
+                ScratchcardDiagnostic::MismatchedColumnCount {
+                    line: 3,
+                    card_id: 3,
+                    expected: (2, 3),
+                    found: (2, 2)
+                },
+            ],
+            validate("Card 1: 1 2 | 3 4 5\nCard 2: 7 1 7 | 3 4 5\nCard 3: 1 2 | 3 4")
+        );
+    }
+
+    #[test]
+    fn diagnostics_display() {
+        assert_eq!(
+            "cards 3 to 5 are missing",
+            ScratchcardDiagnostic::MissingCardIds {
+                first_card_id: 3,
+                last_card_id: 5
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "line 4: card 2 was already defined on line 1",
+            ScratchcardDiagnostic::DuplicateCardId {
+                line: 4,
+                card_id: 2,
+                first_line: 1
+            }
+            .to_string()
+        );
+    }
+}
@@ -2,16 +2,78 @@ use std::fs::File;
 use std::io::prelude::*;
 
 extern crate day_5;
-use day_5::FarmingAlmanac;
+extern crate tracing_subscriber;
+use day_5::{FarmingAlmanac, SeedMode};
+use tracing_subscriber::fmt::format::FmtSpan;
 
 fn main() -> std::io::Result<()> {
+    if std::env::args().any(|argument| argument == "--verbose") {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
     let mut farming_input_file =
         File::open("/home/lpaulic/Documents/github/advent-of-code/2023/day-5/src/bin/data/input")?;
     let mut farming_input = String::new();
 
     farming_input_file.read_to_string(&mut farming_input)?;
-    let farming = FarmingAlmanac::parse(&farming_input);
-    println!("Lowest location number: {}", farming.get_min_location());
+
+    let farming = match FarmingAlmanac::parse(&farming_input) {
+        Ok(farming) => farming,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                eprintln!("{}", diagnostic);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    if std::env::args().any(|argument| argument == "--flattened") {
+        match farming.get_seed_to_location_map() {
+            Ok(seed_to_location_map) => println!("{}", seed_to_location_map),
+            Err(error) => eprintln!("Unable to flatten the almanac maps: {}", error),
+        }
+    }
+
+    let verify = std::env::args().any(|argument| argument == "--verify");
+    for (seed_mode, seed_mode_name) in [
+        (SeedMode::IndividualValues, "individual seeds"),
+        (SeedMode::Ranges, "seed ranges"),
+    ] {
+        match farming.get_min_location(seed_mode) {
+            Ok(min_location) => {
+                println!(
+                    "Lowest location number for {}: {}",
+                    seed_mode_name, min_location
+                );
+
+                if verify {
+                    match farming.get_min_location_brute_force(seed_mode) {
+                        Ok(brute_force_min_location)
+                            if brute_force_min_location == min_location =>
+                        {
+                            println!("Verified lowest location number for {}", seed_mode_name)
+                        }
+                        Ok(brute_force_min_location) => eprintln!(
+                            "Lowest location number mismatch for {}: {} by ranges, {} by brute force",
+                            seed_mode_name, min_location, brute_force_min_location
+                        ),
+                        Err(error) => eprintln!(
+                            "Unable to verify the lowest location number for {}: {}",
+                            seed_mode_name, error
+                        ),
+                    }
+                }
+            }
+            Err(error) => eprintln!(
+                "Unable to find the lowest location number for {}: {}",
+                seed_mode_name, error
+            ),
+        }
+    }
 
     Ok(())
 }
@@ -1,93 +1,476 @@
-mod fertilizer_to_water;
-mod humidity_to_location;
-mod light_to_temperature;
+mod almanac_error;
+mod range_map;
 mod seed;
-mod seed_to_soil;
-mod soil_to_fertilizer;
-mod temperature_to_humidity;
-mod utils;
-mod water_to_light;
-
-use self::fertilizer_to_water::*;
-use self::humidity_to_location::*;
-use self::light_to_temperature::*;
-use self::seed::*;
-use self::seed_to_soil::*;
-use self::soil_to_fertilizer::*;
-use self::temperature_to_humidity::*;
-use self::water_to_light::*;
+mod validation;
+
+pub use self::almanac_error::AlmanacError;
+pub use self::range_map::RangeMap;
+use self::seed::SeedMap;
+pub use self::seed::SeedMode;
+pub use self::validation::AlmanacDiagnostic;
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use tracing::{debug, debug_span, trace};
+
+const SEED_CATEGORY: &str = "seed";
+const LOCATION_CATEGORY: &str = "location";
 
 #[derive(Debug)]
 pub struct FarmingAlmanac {
     seeds: SeedMap,
-    seeds_to_soil_map: SeedToSoilMap,
-    soil_to_fertilizer_map: SoilToFertilizerMap,
-    fertilizer_to_water_map: FertilizerToWaterMap,
-    water_to_light_map: WaterToLightMap,
-    light_to_temperature_map: LightToTemperatureMap,
-    temperature_to_humidity_map: TemperatureToHumidityMap,
-    humidity_to_location_map: HumidityToLocationMap,
+    maps: Vec<RangeMap>,
 }
 
 impl FarmingAlmanac {
-    pub fn parse(almanac: &str) -> Self {
-        FarmingAlmanac {
+    // Only validated almanacs are parsed, so no two entries of a map overlap
+    // and every range fits into 64 bits.
+    pub fn parse(almanac: &str) -> Result<Self, Vec<AlmanacDiagnostic>> {
+        let diagnostics = validation::validate(almanac);
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+
+        Ok(FarmingAlmanac {
             seeds: SeedMap::extract_seeds_from_almanac(almanac),
-            seeds_to_soil_map: SeedToSoilMap::extract_seed_to_soil_map_from_almanac(almanac),
-            soil_to_fertilizer_map:
-                SoilToFertilizerMap::extract_soil_to_fertilizer_map_from_almanac(almanac),
-            fertilizer_to_water_map:
-                FertilizerToWaterMap::extract_fertilizer_to_water_map_from_almanac(almanac),
-            water_to_light_map: WaterToLightMap::extract_water_to_light_map_from_almanac(almanac),
-            light_to_temperature_map:
-                LightToTemperatureMap::extract_light_to_temperature_map_from_almanac(almanac),
-            temperature_to_humidity_map:
-                TemperatureToHumidityMap::extract_temperature_to_humidity_map_from_almanac(almanac),
-            humidity_to_location_map:
-                HumidityToLocationMap::extract_humidity_to_location_map_from_almanac(almanac),
+            maps: RangeMap::extract_range_maps_from_almanac(almanac),
+        })
+    }
+
+    pub fn validate(almanac: &str) -> Vec<AlmanacDiagnostic> {
+        validation::validate(almanac)
+    }
+
+    pub fn get_maps(&self) -> &[RangeMap] {
+        &self.maps
+    }
+
+    // Maps the value between any two categories. Mapping backwards through the
+    // chain can yield several values, as more than one source can map onto the
+    // same destination.
+    pub fn map_value(
+        &self,
+        value: u64,
+        from_category: &str,
+        to_category: &str,
+    ) -> Result<Vec<u64>, AlmanacError> {
+        match self.find_chain(from_category, to_category) {
+            Ok(chain) => Ok(vec![chain.iter().fold(value, |value, map| map.map(value))]),
+            Err(AlmanacError::BrokenChain { .. }) => {
+                let chain =
+                    self.find_chain(to_category, from_category)
+                        .map_err(|error| match error {
+                            AlmanacError::BrokenChain { .. } => AlmanacError::BrokenChain {
+                                from: from_category.to_string(),
+                                to: to_category.to_string(),
+                            },
+                            error => error,
+                        })?;
+
+                Ok(chain.iter().rev().fold(vec![value], |values, map| {
+                    let mut sources: Vec<u64> = values
+                        .iter()
+                        .flat_map(|value| map.inverse_map(*value))
+                        .collect();
+                    sources.sort_unstable();
+                    sources.dedup();
+                    sources
+                }))
+            }
+            Err(error) => Err(error),
         }
     }
 
-    pub fn get_min_location(&self) -> u64 {
+    // Follows the maps from one category to another, requiring exactly one map
+    // to start from each category along the way.
+    fn find_chain(
+        &self,
+        from_category: &str,
+        to_category: &str,
+    ) -> Result<Vec<&RangeMap>, AlmanacError> {
+        for category in [from_category, to_category] {
+            if !self.is_known_category(category) {
+                return Err(AlmanacError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        let mut chain: Vec<&RangeMap> = Vec::new();
+        let mut visited_categories: HashSet<&str> = HashSet::new();
+        let mut category = from_category;
+
+        while category != to_category {
+            let broken_chain_error = AlmanacError::BrokenChain {
+                from: from_category.to_string(),
+                to: to_category.to_string(),
+            };
+
+            if !visited_categories.insert(category) {
+                return Err(broken_chain_error);
+            }
+
+            let mut maps = self
+                .maps
+                .iter()
+                .filter(|map| map.get_source_category() == category);
+
+            let map = maps.next().ok_or(broken_chain_error)?;
+            if maps.next().is_some() {
+                return Err(AlmanacError::AmbiguousChain {
+                    category: category.to_string(),
+                });
+            }
+
+            chain.push(map);
+            category = map.get_destination_category();
+        }
+
+        Ok(chain)
+    }
+
+    // Composes the chain of maps between two categories into a single map,
+    // which can then be looked up with a binary search.
+    pub fn flatten_maps(
+        &self,
+        from_category: &str,
+        to_category: &str,
+    ) -> Result<RangeMap, AlmanacError> {
+        let _span = debug_span!("flatten_maps", from_category, to_category).entered();
+        let chain = self.find_chain(from_category, to_category)?;
+        let Some((first_map, remaining_maps)) = chain.split_first() else {
+            return Err(AlmanacError::BrokenChain {
+                from: from_category.to_string(),
+                to: to_category.to_string(),
+            });
+        };
+
+        Ok(remaining_maps
+            .iter()
+            .fold(first_map.flatten(), |composed_map, map| {
+                composed_map.compose(map)
+            }))
+    }
+
+    pub fn get_seed_to_location_map(&self) -> Result<RangeMap, AlmanacError> {
+        self.flatten_maps(SEED_CATEGORY, LOCATION_CATEGORY)
+    }
+
+    fn is_known_category(&self, category: &str) -> bool {
+        self.maps.iter().any(|map| {
+            map.get_source_category() == category || map.get_destination_category() == category
+        })
+    }
+
+    pub fn get_min_location(&self, seed_mode: SeedMode) -> Result<u64, AlmanacError> {
+        let _span = debug_span!("min_location", ?seed_mode).entered();
+        let seed_ranges: Vec<Range<u64>> = self.seeds.get_ranges(seed_mode)?;
+
+        let location_ranges = self
+            .find_chain(SEED_CATEGORY, LOCATION_CATEGORY)?
+            .iter()
+            .fold(seed_ranges, |ranges, map| {
+                let _span = debug_span!(
+                    "map_ranges",
+                    from = map.get_source_category(),
+                    to = map.get_destination_category()
+                )
+                .entered();
+                let mapped_ranges = map.map_ranges(&ranges);
+                debug!(
+                    input_ranges = ranges.len(),
+                    output_ranges = mapped_ranges.len(),
+                    "mapped ranges"
+                );
+                mapped_ranges
+            });
+
+        Ok(location_ranges
+            .iter()
+            .map(|location_range| location_range.start)
+            .min()
+            .unwrap_or(u64::MAX))
+    }
+
+    pub fn get_min_location_brute_force(&self, seed_mode: SeedMode) -> Result<u64, AlmanacError> {
+        let _span = debug_span!("min_location_brute_force", ?seed_mode).entered();
+        let chain = self.find_chain(SEED_CATEGORY, LOCATION_CATEGORY)?;
         let mut all_seeds_min_location: u64 = u64::MAX;
-        for seed in self.seeds.iter() {
-            println!("seed: {:?}", seed);
-            let seed_min_location = (seed.get_range_start()..seed.get_range_end())
-                .map(|seed| self.seeds_to_soil_map.seed_to_soil(seed))
-                .map(|soil| self.soil_to_fertilizer_map.soil_to_fertilizer(soil))
-                .map(|fertilizer| self.fertilizer_to_water_map.fertilizer_to_water(fertilizer))
-                .map(|water| self.water_to_light_map.water_to_light(water))
-                .map(|light| self.light_to_temperature_map.light_to_temperature(light))
-                .map(|temperature| {
-                    self.temperature_to_humidity_map
-                        .temperature_to_humidity(temperature)
-                })
-                .map(|humidity| self.humidity_to_location_map.humidity_to_location(humidity))
-                .min_by(|location1, location2| location1.cmp(location2))
-                .unwrap();
+        for seed_range in self.seeds.get_ranges(seed_mode)? {
+            let _span = debug_span!("seed_range", ?seed_range).entered();
+            let seed_min_location = FarmingAlmanac::min_location_in_seed_range(&chain, seed_range);
 
+            trace!(seed_min_location, "evaluated seed range");
             if seed_min_location < all_seeds_min_location {
                 all_seeds_min_location = seed_min_location;
             }
         }
 
-        all_seeds_min_location
+        Ok(all_seeds_min_location)
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn min_location_in_seed_range(chain: &[&RangeMap], seed_range: Range<u64>) -> u64 {
+        seed_range
+            .map(|seed| chain.iter().fold(seed, |value, map| map.map(value)))
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    // Splits the seed range into chunks evaluated on the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    fn min_location_in_seed_range(chain: &[&RangeMap], seed_range: Range<u64>) -> u64 {
+        use rayon::prelude::*;
+
+        seed_range
+            .into_par_iter()
+            .map(|seed| chain.iter().fold(seed, |value, map| map.map(value)))
+            .min()
+            .unwrap_or(u64::MAX)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::XorShift;
 
-    fn assert_min_location_value(expected_min_location_value: u64, almanac: &str) {
+    fn assert_min_location_value(
+        expected_min_location_value: u64,
+        seed_mode: SeedMode,
+        almanac: &str,
+    ) {
+        assert_eq!(
+            Ok(expected_min_location_value),
+            FarmingAlmanac::parse(almanac)
+                .unwrap()
+                .get_min_location(seed_mode)
+        );
         assert_eq!(
-            expected_min_location_value,
-            FarmingAlmanac::parse(almanac).get_min_location()
+            Ok(expected_min_location_value),
+            FarmingAlmanac::parse(almanac)
+                .unwrap()
+                .get_min_location_brute_force(seed_mode)
         );
     }
 
+    fn generate_almanac(random: &mut XorShift) -> String {
+        const MAP_HEADERS: [&str; 7] = [
+            "seed-to-soil map:",
+            "soil-to-fertilizer map:",
+            "fertilizer-to-water map:",
+            "water-to-light map:",
+            "light-to-temperature map:",
+            "temperature-to-humidity map:",
+            "humidity-to-location map:",
+        ];
+
+        let seeds: Vec<String> = (0..random.next(4) + 1)
+            .map(|_| format!("{} {}", random.next(200), random.next(50) + 1))
+            .collect();
+        let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+
+        // Source ranges of a map must not overlap, so each one starts after
+        // the previous one ends; destination ranges may still overlap.
+        for map_header in MAP_HEADERS {
+            almanac.push_str(&format!("\n{}\n", map_header));
+            let mut source_range_start = random.next(60);
+            for _ in 0..random.next(5) {
+                let range_size = random.next(60) + 1;
+                almanac.push_str(&format!(
+                    "{} {} {}\n",
+                    random.next(300),
+                    source_range_start,
+                    range_size
+                ));
+                source_range_start += range_size + random.next(30);
+            }
+        }
+
+        almanac
+    }
+
+    #[test]
+    fn range_mapping_matches_brute_force_on_random_almanacs() {
+        let mut random = XorShift::default();
+
+        for _ in 0..500 {
+            let almanac = FarmingAlmanac::parse(&generate_almanac(&mut random)).unwrap();
+            for seed_mode in [SeedMode::IndividualValues, SeedMode::Ranges] {
+                assert_eq!(
+                    almanac.get_min_location_brute_force(seed_mode),
+                    almanac.get_min_location(seed_mode)
+                );
+            }
+        }
+    }
+
     #[test]
     fn minimal_location_value_from_almanac() {
-        assert_min_location_value(46, "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n\nfertilizer-to-water map:\n49 53 8\n0 11 42\n42 0 7\n57 7 4\n\n\nwater-to-light map:\n88 18 7\n18 25 70\n\n\nlight-to-temperature map:\n45 77 23\n81 45 19\n68 64 13\n\n\ntemperature-to-humidity map:\n0 69 1\n1 0 69\n\nhumidity-to-location map:\n60 56 37\n56 93 4\n");
+        assert_min_location_value(46, SeedMode::Ranges, "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n\nfertilizer-to-water map:\n49 53 8\n0 11 42\n42 0 7\n57 7 4\n\n\nwater-to-light map:\n88 18 7\n18 25 70\n\n\nlight-to-temperature map:\n45 77 23\n81 45 19\n68 64 13\n\n\ntemperature-to-humidity map:\n0 69 1\n1 0 69\n\nhumidity-to-location map:\n60 56 37\n56 93 4\n");
+    }
+
+    const EXAMPLE_ALMANAC: &str = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n\nfertilizer-to-water map:\n49 53 8\n0 11 42\n42 0 7\n57 7 4\n\n\nwater-to-light map:\n88 18 7\n18 25 70\n\n\nlight-to-temperature map:\n45 77 23\n81 45 19\n68 64 13\n\n\ntemperature-to-humidity map:\n0 69 1\n1 0 69\n\nhumidity-to-location map:\n60 56 37\n56 93 4\n";
+
+    #[test]
+    fn minimal_location_value_from_individual_seeds() {
+        assert_min_location_value(35, SeedMode::IndividualValues, EXAMPLE_ALMANAC);
+    }
+
+    #[test]
+    fn odd_seed_count_is_an_error_for_seed_ranges() {
+        let almanac =
+            FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen("79 14 55 13", "79 14 55", 1)).unwrap();
+
+        assert_eq!(
+            Err(AlmanacError::OddSeedCount(3)),
+            almanac.get_min_location(SeedMode::Ranges)
+        );
+        assert_eq!(Ok(43), almanac.get_min_location(SeedMode::IndividualValues));
+    }
+
+    #[test]
+    fn malformed_almanacs_are_not_parsed() {
+        assert_eq!(
+            vec![AlmanacDiagnostic::MalformedMapping {
+                map: "seed-to-soil".to_string(),
+                line: 4
+            }],
+            FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen("50 98 2", "0 10", 1)).unwrap_err()
+        );
+        assert_eq!(
+            vec![AlmanacDiagnostic::OverlappingSourceRanges {
+                map: "seed-to-soil".to_string(),
+                line: 5,
+                overlapping_line: 4
+            }],
+            FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen("52 50 48", "52 50 49", 1))
+                .unwrap_err()
+        );
+        assert_eq!(
+            vec![AlmanacDiagnostic::SeedOverflow {
+                line: 1,
+                seed: "99999999999999999999".to_string()
+            }],
+            FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen("79", "99999999999999999999", 1))
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn overflowing_seed_ranges_are_an_error() {
+        let almanac = FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen(
+            "79 14 55 13",
+            "18446744073709551615 5",
+            1,
+        ))
+        .unwrap();
+        assert_eq!(
+            Err(AlmanacError::SeedRangeOverflow {
+                start: u64::MAX,
+                length: 5
+            }),
+            almanac.get_min_location(SeedMode::Ranges)
+        );
+        assert_eq!(
+            Err(AlmanacError::SeedRangeOverflow {
+                start: u64::MAX,
+                length: 5
+            }),
+            almanac.get_min_location_brute_force(SeedMode::Ranges)
+        );
+    }
+
+    #[test]
+    fn overflowing_individual_seed_is_an_error() {
+        let almanac = FarmingAlmanac::parse(&EXAMPLE_ALMANAC.replacen(
+            "79 14 55 13",
+            "79 18446744073709551615",
+            1,
+        ))
+        .unwrap();
+        assert_eq!(
+            Err(AlmanacError::SeedRangeOverflow {
+                start: u64::MAX,
+                length: 1
+            }),
+            almanac.get_min_location(SeedMode::IndividualValues)
+        );
+    }
+
+    #[test]
+    fn flattened_seed_to_location_map_matches_chain_on_random_almanacs() {
+        let mut random = XorShift::default();
+
+        for _ in 0..200 {
+            let almanac = FarmingAlmanac::parse(&generate_almanac(&mut random)).unwrap();
+            let seed_to_location_map = almanac.get_seed_to_location_map().unwrap();
+
+            for seed in 0..400 {
+                assert_eq!(
+                    almanac.map_value(seed, "seed", "location").unwrap(),
+                    vec![seed_to_location_map.map(seed)]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn map_value_forward_between_categories() {
+        let almanac = FarmingAlmanac::parse(EXAMPLE_ALMANAC).unwrap();
+
+        assert_eq!(Ok(vec![81]), almanac.map_value(79, "seed", "soil"));
+        assert_eq!(Ok(vec![78]), almanac.map_value(79, "seed", "humidity"));
+        assert_eq!(Ok(vec![82]), almanac.map_value(79, "seed", "location"));
+        assert_eq!(Ok(vec![74]), almanac.map_value(74, "light", "light"));
+    }
+
+    #[test]
+    fn map_value_backward_between_categories() {
+        let almanac = FarmingAlmanac::parse(EXAMPLE_ALMANAC).unwrap();
+
+        assert_eq!(Ok(vec![79]), almanac.map_value(74, "light", "seed"));
+        assert_eq!(Ok(vec![79]), almanac.map_value(82, "location", "seed"));
+        assert_eq!(Ok(vec![14]), almanac.map_value(43, "location", "seed"));
+    }
+
+    #[test]
+    fn map_value_with_categories_in_any_order() {
+        let almanac =
+            FarmingAlmanac::parse("seeds: 1 1\n\nb-to-c map:\n10 0 5\n\na-to-b map:\n0 10 5\n")
+                .unwrap();
+
+        assert_eq!(Ok(vec![12]), almanac.map_value(12, "a", "c"));
+        assert_eq!(Ok(vec![2]), almanac.map_value(12, "a", "b"));
+        assert_eq!(Ok(vec![2, 12]), almanac.map_value(2, "b", "a"));
+    }
+
+    #[test]
+    fn map_value_errors() {
+        let almanac = FarmingAlmanac::parse(
+            "seeds: 1 1\n\na-to-b map:\n0 10 5\n\na-to-c map:\n0 10 5\n\nd-to-e map:\n0 10 5\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(AlmanacError::UnknownCategory("x".to_string())),
+            almanac.map_value(1, "x", "b")
+        );
+        assert_eq!(
+            Err(AlmanacError::AmbiguousChain {
+                category: "a".to_string()
+            }),
+            almanac.map_value(1, "a", "b")
+        );
+        assert_eq!(
+            Err(AlmanacError::BrokenChain {
+                from: "b".to_string(),
+                to: "e".to_string()
+            }),
+            almanac.map_value(1, "b", "e")
+        );
+        assert_eq!(
+            Err(AlmanacError::UnknownCategory("seed".to_string())),
+            almanac.get_min_location(SeedMode::IndividualValues)
+        );
     }
 }
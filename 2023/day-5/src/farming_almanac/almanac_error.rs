@@ -0,0 +1,36 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlmanacError {
+    UnknownCategory(String),
+    BrokenChain { from: String, to: String },
+    AmbiguousChain { category: String },
+    OddSeedCount(usize),
+    SeedRangeOverflow { start: u64, length: u64 },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::UnknownCategory(category) => {
+                write!(f, "no map starts or ends in category `{}`", category)
+            }
+            AlmanacError::BrokenChain { from, to } => {
+                write!(f, "no chain of maps leads from `{}` to `{}`", from, to)
+            }
+            AlmanacError::AmbiguousChain { category } => {
+                write!(f, "more than one map starts from category `{}`", category)
+            }
+            AlmanacError::OddSeedCount(seed_count) => write!(
+                f,
+                "seed ranges need a start and a length, but {} seed values were given",
+                seed_count
+            ),
+            AlmanacError::SeedRangeOverflow { start, length } => write!(
+                f,
+                "seed range starting at {} with length {} does not fit into 64 bits",
+                start, length
+            ),
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use std::fmt;
+use std::ops::Range;
+
+use tracing::{debug, trace};
+
+#[derive(Debug)]
+pub struct RangeMap {
+    source_category: String,
+    destination_category: String,
+    map: Vec<RangeMapping>,
+    // Flattened maps hold sorted, disjoint mappings and are searched with a
+    // binary search instead of by the first matching mapping.
+    flattened: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RangeMapping {
+    source_range_start: u64,
+    destination_range_start: u64,
+    range_size: u64,
+}
+
+impl RangeMapping {
+    fn parse(mapping_line: &str) -> Option<Self> {
+        let mapping: Vec<Option<u64>> = mapping_line
+            .split_whitespace()
+            .map(|number| number.parse::<u64>().ok())
+            .collect();
+        let [Some(destination_range_start), Some(source_range_start), Some(range_size)] =
+            mapping[..]
+        else {
+            return None;
+        };
+
+        Some(RangeMapping {
+            source_range_start,
+            destination_range_start,
+            range_size,
+        })
+    }
+}
+
+impl RangeMap {
+    // Entries that are not three numbers are skipped, validation reports them.
+    pub(crate) fn extract_range_maps_from_almanac(almanac: &str) -> Vec<RangeMap> {
+        let mut range_maps: Vec<RangeMap> = Vec::new();
+        let mut lines = almanac.split('\n').peekable();
+
+        while let Some(line) = lines.next() {
+            let Some((source_category, destination_category)) = RangeMap::parse_header(line) else {
+                continue;
+            };
+
+            let mut map: Vec<RangeMapping> = Vec::new();
+            while let Some(mapping_line) = lines.next_if(|line| !line.trim().is_empty()) {
+                if let Some(mapping) = RangeMapping::parse(mapping_line) {
+                    map.push(mapping);
+                }
+            }
+
+            range_maps.push(RangeMap {
+                source_category,
+                destination_category,
+                map,
+                flattened: false,
+            });
+        }
+
+        range_maps
+    }
+
+    pub(crate) fn parse_header(line: &str) -> Option<(String, String)> {
+        let categories = line.trim().to_lowercase();
+        let categories = categories.strip_suffix("map:")?.trim();
+        let (source_category, destination_category) = categories.split_once("-to-")?;
+
+        Some((
+            source_category.to_string(),
+            destination_category.to_string(),
+        ))
+    }
+
+    pub fn get_source_category(&self) -> &str {
+        &self.source_category
+    }
+
+    pub fn get_destination_category(&self) -> &str {
+        &self.destination_category
+    }
+
+    pub fn map(&self, source: u64) -> u64 {
+        match self.find_mapping(source) {
+            Some(mapping) => {
+                mapping.destination_range_start + (source - mapping.source_range_start)
+            }
+            None => source,
+        }
+    }
+
+    // All source values that map onto the destination value, in ascending order.
+    pub fn inverse_map(&self, destination: u64) -> Vec<u64> {
+        let mut sources: Vec<u64> = self
+            .map
+            .iter()
+            .filter(|mapping| {
+                destination >= mapping.destination_range_start
+                    && destination - mapping.destination_range_start < mapping.range_size
+            })
+            .map(|mapping| {
+                mapping.source_range_start + (destination - mapping.destination_range_start)
+            })
+            .chain(std::iter::once(destination))
+            .filter(|source| self.map(*source) == destination)
+            .collect();
+
+        sources.sort_unstable();
+        sources.dedup();
+        sources
+    }
+
+    // Maps every range through the first mapping covering each of its parts,
+    // splitting ranges at mapping boundaries. Uncovered parts map to themselves.
+    pub fn map_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        self.map_range_pieces(ranges)
+            .into_iter()
+            .map(|(piece, destination_start)| {
+                destination_start..destination_start + (piece.end - piece.start)
+            })
+            .collect()
+    }
+
+    // Composes this map with the next one into a single flattened map going
+    // straight from this map's source to the next map's destination.
+    pub fn compose(&self, next: &RangeMap) -> RangeMap {
+        let mut map: Vec<RangeMapping> = Vec::new();
+
+        for segment in self.covering_segments() {
+            let segment_image = segment.destination_range_start
+                ..segment.destination_range_start + segment.range_size;
+
+            for (piece, destination_start) in
+                next.map_range_pieces(std::slice::from_ref(&segment_image))
+            {
+                map.push(RangeMapping {
+                    source_range_start: segment.source_range_start
+                        + (piece.start - segment.destination_range_start),
+                    destination_range_start: destination_start,
+                    range_size: piece.end - piece.start,
+                });
+            }
+        }
+
+        debug!(
+            from = self.source_category,
+            through = next.source_category,
+            to = next.destination_category,
+            segments = map.len(),
+            "composed maps"
+        );
+
+        RangeMap::create_flattened(
+            self.source_category.clone(),
+            next.destination_category.clone(),
+            map,
+        )
+    }
+
+    pub fn flatten(&self) -> RangeMap {
+        RangeMap::create_flattened(
+            self.source_category.clone(),
+            self.destination_category.clone(),
+            self.covering_segments(),
+        )
+    }
+
+    // (source range start, destination range start, range size) of every
+    // mapping, in the order they are searched.
+    pub fn get_mappings(&self) -> Vec<(u64, u64, u64)> {
+        self.map
+            .iter()
+            .map(|mapping| {
+                (
+                    mapping.source_range_start,
+                    mapping.destination_range_start,
+                    mapping.range_size,
+                )
+            })
+            .collect()
+    }
+
+    fn create_flattened(
+        source_category: String,
+        destination_category: String,
+        mut map: Vec<RangeMapping>,
+    ) -> RangeMap {
+        map.retain(|mapping| {
+            mapping.range_size > 0 && mapping.source_range_start != mapping.destination_range_start
+        });
+        map.sort_by_key(|mapping| mapping.source_range_start);
+
+        let mut merged_map: Vec<RangeMapping> = Vec::new();
+        for mapping in map {
+            match merged_map.last_mut() {
+                Some(previous)
+                    if previous.source_range_start + previous.range_size
+                        == mapping.source_range_start
+                        && previous.destination_range_start + previous.range_size
+                            == mapping.destination_range_start =>
+                {
+                    previous.range_size += mapping.range_size;
+                }
+                _ => merged_map.push(mapping),
+            }
+        }
+
+        RangeMap {
+            source_category,
+            destination_category,
+            map: merged_map,
+            flattened: true,
+        }
+    }
+
+    // Splits the ranges at mapping boundaries, pairing every piece with the
+    // start of the range it maps to.
+    fn map_range_pieces(&self, ranges: &[Range<u64>]) -> Vec<(Range<u64>, u64)> {
+        let mut unmapped_ranges: Vec<Range<u64>> = ranges
+            .iter()
+            .filter(|range| !range.is_empty())
+            .cloned()
+            .collect();
+        let mut mapped_pieces: Vec<(Range<u64>, u64)> = Vec::new();
+
+        for mapping in &self.map {
+            let source_range_end = mapping
+                .source_range_start
+                .saturating_add(mapping.range_size);
+            let mut remaining_ranges: Vec<Range<u64>> = Vec::new();
+
+            for range in unmapped_ranges {
+                let overlap_start = range.start.max(mapping.source_range_start);
+                let overlap_end = range.end.min(source_range_end);
+
+                if overlap_start >= overlap_end {
+                    remaining_ranges.push(range);
+                    continue;
+                }
+
+                trace!(
+                    ?range,
+                    overlap = ?(overlap_start..overlap_end),
+                    mapping_source_start = mapping.source_range_start,
+                    "split range"
+                );
+                mapped_pieces.push((
+                    overlap_start..overlap_end,
+                    mapping.destination_range_start + (overlap_start - mapping.source_range_start),
+                ));
+
+                if range.start < overlap_start {
+                    remaining_ranges.push(range.start..overlap_start);
+                }
+                if overlap_end < range.end {
+                    remaining_ranges.push(overlap_end..range.end);
+                }
+            }
+
+            unmapped_ranges = remaining_ranges;
+        }
+
+        mapped_pieces.extend(
+            unmapped_ranges
+                .into_iter()
+                .map(|range| (range.clone(), range.start)),
+        );
+        mapped_pieces
+    }
+
+    // Sorted, disjoint mappings covering every source value, with the values
+    // no mapping covers mapped onto themselves.
+    fn covering_segments(&self) -> Vec<RangeMapping> {
+        let mut segments: Vec<RangeMapping> = self
+            .map_range_pieces(std::slice::from_ref(&(0..u64::MAX)))
+            .into_iter()
+            .map(|(piece, destination_start)| RangeMapping {
+                source_range_start: piece.start,
+                destination_range_start: destination_start,
+                range_size: piece.end - piece.start,
+            })
+            .collect();
+
+        segments.sort_by_key(|segment| segment.source_range_start);
+        segments
+    }
+
+    fn find_mapping(&self, source: u64) -> Option<&RangeMapping> {
+        if self.flattened {
+            let mapping_index = self.map.partition_point(|mapping| {
+                mapping.source_range_start + mapping.range_size <= source
+            });
+
+            return self
+                .map
+                .get(mapping_index)
+                .filter(|mapping| source >= mapping.source_range_start);
+        }
+
+        self.map.iter().find(|mapping| {
+            source >= mapping.source_range_start
+                && source - mapping.source_range_start < mapping.range_size
+        })
+    }
+}
+
+impl fmt::Display for RangeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}-to-{} map:",
+            self.source_category, self.destination_category
+        )?;
+
+        for mapping in &self.map {
+            writeln!(
+                f,
+                "{} {} {}",
+                mapping.destination_range_start, mapping.source_range_start, mapping.range_size
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_range_map(almanac: &str) -> RangeMap {
+        RangeMap::extract_range_maps_from_almanac(almanac)
+            .into_iter()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn range_maps_discovered_from_headers() {
+        let range_maps = RangeMap::extract_range_maps_from_almanac(
+            "seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-fertilizer map:\n0 15 37\n",
+        );
+
+        assert_eq!(2, range_maps.len());
+        assert_eq!("seed", range_maps[0].get_source_category());
+        assert_eq!("soil", range_maps[0].get_destination_category());
+        assert_eq!(2, range_maps[0].map.len());
+        assert_eq!("soil", range_maps[1].get_source_category());
+        assert_eq!("fertilizer", range_maps[1].get_destination_category());
+        assert_eq!(1, range_maps[1].map.len());
+    }
+
+    #[test]
+    fn range_map_lookup() {
+        let range_map = parse_range_map("seed-to-soil map:\n50 98 2\n52 50 48\n");
+
+        assert_eq!(81, range_map.map(79));
+        assert_eq!(14, range_map.map(14));
+        assert_eq!(57, range_map.map(55));
+        assert_eq!(13, range_map.map(13));
+        assert_eq!(50, range_map.map(98));
+        assert_eq!(100, range_map.map(100));
+    }
+
+    #[test]
+    fn range_map_inverse_lookup() {
+        let range_map = parse_range_map("seed-to-soil map:\n50 98 2\n52 50 48\n");
+
+        assert_eq!(vec![79], range_map.inverse_map(81));
+        assert_eq!(vec![14], range_map.inverse_map(14));
+        assert_eq!(vec![98], range_map.inverse_map(50));
+        assert_eq!(vec![50], range_map.inverse_map(52));
+        assert_eq!(vec![97], range_map.inverse_map(99));
+
+        let range_map = parse_range_map("a-to-b map:\n0 10 5\n");
+        assert_eq!(vec![2, 12], range_map.inverse_map(2));
+        assert!(range_map.inverse_map(12).is_empty());
+    }
+
+    #[test]
+    fn map_ranges_splits_at_mapping_boundaries() {
+        let range_map = parse_range_map("seed-to-soil map:\n50 98 2\n52 50 48\n");
+        let mut mapped_ranges = range_map.map_ranges(&[79..93, 55..68]);
+        mapped_ranges.sort_by_key(|range| range.start);
+        assert_eq!(vec![57..70, 81..95], mapped_ranges);
+
+        let range_map = parse_range_map("a-to-b map:\n100 5 5\n200 12 20\n");
+        let mut mapped_ranges = range_map.map_ranges(std::slice::from_ref(&(0..20)));
+        mapped_ranges.sort_by_key(|range| range.start);
+        assert_eq!(vec![0..5, 10..12, 100..105, 200..208], mapped_ranges);
+    }
+
+    #[test]
+    fn map_ranges_prefers_first_overlapping_mapping() {
+        let range_map = parse_range_map("a-to-b map:\n100 0 10\n200 0 10\n");
+        assert_eq!(
+            vec![100..110],
+            range_map.map_ranges(std::slice::from_ref(&(0..10)))
+        );
+    }
+
+    #[test]
+    fn flatten_resolves_overlapping_mappings() {
+        let range_map = parse_range_map("a-to-b map:\n100 0 10\n200 5 10\n5 5 2\n");
+        let flattened_map = range_map.flatten();
+
+        assert_eq!(
+            vec![(0, 100, 10), (10, 205, 5)],
+            flattened_map.get_mappings()
+        );
+        for value in 0..30 {
+            assert_eq!(range_map.map(value), flattened_map.map(value));
+        }
+    }
+
+    #[test]
+    fn compose_range_maps() {
+        let range_maps = RangeMap::extract_range_maps_from_almanac(
+            "seed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n",
+        );
+        let composed_map = range_maps[0].compose(&range_maps[1]);
+
+        assert_eq!("seed", composed_map.get_source_category());
+        assert_eq!("fertilizer", composed_map.get_destination_category());
+        for value in 0..200 {
+            assert_eq!(
+                range_maps[1].map(range_maps[0].map(value)),
+                composed_map.map(value)
+            );
+        }
+    }
+
+    #[test]
+    fn range_map_display() {
+        let range_map = parse_range_map("seed-to-soil map:\n52 50 48\n50 98 2\n");
+
+        assert_eq!(
+            "seed-to-soil map:\n52 50 48\n50 98 2\n",
+            range_map.to_string()
+        );
+        assert_eq!(
+            "seed-to-soil map:\n52 50 48\n50 98 2\n",
+            range_map.flatten().to_string()
+        );
+    }
+}
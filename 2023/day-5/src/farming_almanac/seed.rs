@@ -1,52 +1,70 @@
-#[derive(Debug)]
-pub struct SeedMap {
-    map: Vec<Seed>,
+use std::ops::Range;
+
+use super::almanac_error::AlmanacError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedMode {
+    IndividualValues,
+    Ranges,
 }
 
 #[derive(Debug)]
-pub struct Seed {
-    seed_range_start: u64,
-    range_size: u64,
+pub struct SeedMap {
+    seeds: Vec<u64>,
 }
 
 impl SeedMap {
     pub fn extract_seeds_from_almanac(almanac: &str) -> SeedMap {
-        let mut map: Vec<Seed> = Vec::new();
-
         let seeds: Vec<u64> = almanac
             .split('\n')
-            .filter(|line| line.to_lowercase().contains("seeds:"))
-            .collect::<String>()
-            .chars()
-            .filter(|character| character.is_whitespace() || character.is_ascii_digit())
-            .collect::<String>()
-            .split_whitespace()
-            .map(|digit| digit.trim().parse::<u64>().unwrap())
+            .filter(|line| SeedMap::is_seed_line(line))
+            .flat_map(SeedMap::seed_values)
+            .filter_map(|seed| seed.parse::<u64>().ok())
             .collect();
 
-        let mut i = 0;
-        while i < seeds.len() {
-            map.push(Seed {
-                seed_range_start: seeds[i],
-                range_size: seeds[i + 1],
-            });
-            i += 2;
-        }
+        SeedMap { seeds }
+    }
 
-        SeedMap { map }
+    pub(crate) fn is_seed_line(line: &str) -> bool {
+        line.to_lowercase().contains("seeds:")
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Seed> {
-        self.map.iter()
+    // Only the digits and whitespace of a seed line are kept, so every value
+    // is a number, although not necessarily one that fits into 64 bits.
+    pub(crate) fn seed_values(line: &str) -> Vec<String> {
+        line.chars()
+            .filter(|character| character.is_whitespace() || character.is_ascii_digit())
+            .collect::<String>()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
     }
-}
 
-impl Seed {
-    pub fn get_range_start(&self) -> u64 {
-        self.seed_range_start
+    pub fn get_ranges(&self, seed_mode: SeedMode) -> Result<Vec<Range<u64>>, AlmanacError> {
+        match seed_mode {
+            SeedMode::IndividualValues => self
+                .seeds
+                .iter()
+                .map(|&seed| SeedMap::seed_range(seed, 1))
+                .collect(),
+            SeedMode::Ranges => {
+                if !self.seeds.len().is_multiple_of(2) {
+                    return Err(AlmanacError::OddSeedCount(self.seeds.len()));
+                }
+
+                self.seeds
+                    .chunks(2)
+                    .map(|seed_range| SeedMap::seed_range(seed_range[0], seed_range[1]))
+                    .collect()
+            }
+        }
     }
 
-    pub fn get_range_end(&self) -> u64 {
-        self.seed_range_start + self.range_size
+    fn seed_range(start: u64, length: u64) -> Result<Range<u64>, AlmanacError> {
+        let end = start
+            .checked_add(length)
+            .ok_or(AlmanacError::SeedRangeOverflow { start, length })?;
+
+        Ok(start..end)
     }
 }
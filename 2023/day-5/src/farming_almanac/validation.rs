@@ -0,0 +1,254 @@
+use std::fmt;
+
+use super::range_map::RangeMap;
+use super::seed::SeedMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlmanacDiagnostic {
+    SeedOverflow {
+        line: usize,
+        seed: String,
+    },
+    MalformedMapping {
+        map: String,
+        line: usize,
+    },
+    ZeroLengthRange {
+        map: String,
+        line: usize,
+    },
+    RangeOverflow {
+        map: String,
+        line: usize,
+    },
+    OverlappingSourceRanges {
+        map: String,
+        line: usize,
+        overlapping_line: usize,
+    },
+}
+
+impl fmt::Display for AlmanacDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacDiagnostic::SeedOverflow { line, seed } => {
+                write!(f, "line {}: seed {} does not fit into 64 bits", line, seed)
+            }
+            AlmanacDiagnostic::MalformedMapping { map, line } => write!(
+                f,
+                "line {}: {} map entry is not `<destination> <source> <length>`",
+                line, map
+            ),
+            AlmanacDiagnostic::ZeroLengthRange { map, line } => {
+                write!(
+                    f,
+                    "line {}: {} map entry has a zero length range",
+                    line, map
+                )
+            }
+            AlmanacDiagnostic::RangeOverflow { map, line } => write!(
+                f,
+                "line {}: {} map entry range does not fit into 64 bits",
+                line, map
+            ),
+            AlmanacDiagnostic::OverlappingSourceRanges {
+                map,
+                line,
+                overlapping_line,
+            } => write!(
+                f,
+                "line {}: {} map entry source range overlaps the one on line {}",
+                line, map, overlapping_line
+            ),
+        }
+    }
+}
+
+struct MapEntry {
+    line: usize,
+    source_range: (u64, u64),
+}
+
+pub fn validate(almanac: &str) -> Vec<AlmanacDiagnostic> {
+    let mut diagnostics: Vec<AlmanacDiagnostic> = Vec::new();
+    let mut lines = almanac.split('\n').enumerate().peekable();
+
+    while let Some((line_index, header_line)) = lines.next() {
+        if SeedMap::is_seed_line(header_line) {
+            for seed in SeedMap::seed_values(header_line) {
+                if seed.parse::<u64>().is_err() {
+                    diagnostics.push(AlmanacDiagnostic::SeedOverflow {
+                        line: line_index + 1,
+                        seed,
+                    });
+                }
+            }
+        }
+
+        let Some((source_category, destination_category)) = RangeMap::parse_header(header_line)
+        else {
+            continue;
+        };
+
+        let map = format!("{}-to-{}", source_category, destination_category);
+        let mut map_entries: Vec<MapEntry> = Vec::new();
+
+        while let Some((line_index, mapping_line)) =
+            lines.next_if(|(_, line)| !line.trim().is_empty())
+        {
+            let line = line_index + 1;
+            let mapping: Vec<Option<u64>> = mapping_line
+                .split_whitespace()
+                .map(|number| number.parse::<u64>().ok())
+                .collect();
+
+            let [Some(destination_range_start), Some(source_range_start), Some(range_size)] =
+                mapping[..]
+            else {
+                diagnostics.push(AlmanacDiagnostic::MalformedMapping {
+                    map: map.clone(),
+                    line,
+                });
+                continue;
+            };
+
+            if range_size == 0 {
+                diagnostics.push(AlmanacDiagnostic::ZeroLengthRange {
+                    map: map.clone(),
+                    line,
+                });
+                continue;
+            }
+
+            let (Some(source_range_end), Some(_)) = (
+                source_range_start.checked_add(range_size),
+                destination_range_start.checked_add(range_size),
+            ) else {
+                diagnostics.push(AlmanacDiagnostic::RangeOverflow {
+                    map: map.clone(),
+                    line,
+                });
+                continue;
+            };
+
+            for map_entry in &map_entries {
+                if source_range_start < map_entry.source_range.1
+                    && map_entry.source_range.0 < source_range_end
+                {
+                    diagnostics.push(AlmanacDiagnostic::OverlappingSourceRanges {
+                        map: map.clone(),
+                        line,
+                        overlapping_line: map_entry.line,
+                    });
+                }
+            }
+
+            map_entries.push(MapEntry {
+                line,
+                source_range: (source_range_start, source_range_end),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_almanac_has_no_diagnostics() {
+        assert!(validate("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-fertilizer map:\n0 15 37\n").is_empty());
+    }
+
+    #[test]
+    fn malformed_and_zero_length_entries() {
+        assert_eq!(
+            vec![
+                AlmanacDiagnostic::MalformedMapping {
+                    map: "seed-to-soil".to_string(),
+                    line: 4
+                },
+                AlmanacDiagnostic::MalformedMapping {
+                    map: "seed-to-soil".to_string(),
+                    line: 5
+                },
+                AlmanacDiagnostic::ZeroLengthRange {
+                    map: "seed-to-soil".to_string(),
+                    line: 6
+                },
+            ],
+            validate("seeds: 1 2\n\nseed-to-soil map:\n50 98\n50 x 2\n1 2 0\n")
+        );
+    }
+
+    #[test]
+    fn overflowing_entries() {
+        assert_eq!(
+            vec![
+                AlmanacDiagnostic::RangeOverflow {
+                    map: "a-to-b".to_string(),
+                    line: 2
+                },
+                AlmanacDiagnostic::RangeOverflow {
+                    map: "a-to-b".to_string(),
+                    line: 3
+                },
+            ],
+            validate(
+                "a-to-b map:\n0 18446744073709551615 2\n18446744073709551615 0 2\n0 18446744073709551614 1\n"
+            )
+        );
+    }
+
+    #[test]
+    fn overflowing_seeds() {
+        assert_eq!(
+            vec![AlmanacDiagnostic::SeedOverflow {
+                line: 1,
+                seed: "18446744073709551616".to_string()
+            }],
+            validate("seeds: 18446744073709551615 18446744073709551616\n")
+        );
+    }
+
+    #[test]
+    fn overlapping_source_ranges() {
+        assert_eq!(
+            vec![
+                AlmanacDiagnostic::OverlappingSourceRanges {
+                    map: "a-to-b".to_string(),
+                    line: 3,
+                    overlapping_line: 2
+                },
+                AlmanacDiagnostic::OverlappingSourceRanges {
+                    map: "b-to-c".to_string(),
+                    line: 9,
+                    overlapping_line: 7
+                },
+                AlmanacDiagnostic::OverlappingSourceRanges {
+                    map: "b-to-c".to_string(),
+                    line: 9,
+                    overlapping_line: 8
+                },
+            ],
+            validate(
+                "a-to-b map:\n0 10 5\n100 14 5\n200 19 5\n\nb-to-c map:\n0 0 10\n0 10 10\n0 5 10\n"
+            )
+        );
+    }
+
+    #[test]
+    fn diagnostics_display() {
+        assert_eq!(
+            "line 9: b-to-c map entry source range overlaps the one on line 7",
+            AlmanacDiagnostic::OverlappingSourceRanges {
+                map: "b-to-c".to_string(),
+                line: 9,
+                overlapping_line: 7
+            }
+            .to_string()
+        );
+    }
+}
@@ -1,2 +1,8 @@
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(test)]
+extern crate test_support;
+extern crate tracing;
+
 mod farming_almanac;
-pub use farming_almanac::FarmingAlmanac;
+pub use farming_almanac::{AlmanacDiagnostic, AlmanacError, FarmingAlmanac, RangeMap, SeedMode};
@@ -2,7 +2,7 @@ use std::fs::File;
 use std::io::prelude::*;
 
 extern crate day_6;
-use day_6::RaceStatistics;
+use day_6::{RaceModel, RaceParseMode, RaceStatistics};
 
 fn main() -> std::io::Result<()> {
     let mut race_statistics_input_file =
@@ -10,17 +10,66 @@ fn main() -> std::io::Result<()> {
     let mut race_statistics_input = String::new();
 
     race_statistics_input_file.read_to_string(&mut race_statistics_input)?;
-    let race_statistics_part1 = RaceStatistics::parse(&race_statistics_input, false);
-    let race_statistics_part2 = RaceStatistics::parse(&race_statistics_input, true);
-    println!(
-        "Product of record break opportunities(with whitespace): {}",
-        race_statistics_part1.get_race_record_break_product()
-    );
-
-    println!(
-        "Product of record break opportunities(without whitespace): {}",
-        race_statistics_part2.get_race_record_break_product()
-    );
+    let race_statistics = match RaceStatistics::parse(&race_statistics_input) {
+        Ok(race_statistics) => race_statistics,
+        Err(error) => {
+            eprintln!("Unable to parse the race record table: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    for (parse_mode, parse_mode_name) in [
+        (RaceParseMode::SeparateRaces, "separate races"),
+        (RaceParseMode::KernedRace, "kerned race"),
+    ] {
+        let race_reports = match race_statistics.report(parse_mode, &RaceModel::default()) {
+            Ok(race_reports) => race_reports,
+            Err(error) => {
+                eprintln!("Unable to report the {}: {}", parse_mode_name, error);
+                continue;
+            }
+        };
+
+        println!("Race report({}):", parse_mode_name);
+        println!(
+            "{:>16} | {:>16} | {:>33} | {:>16} | {:>16}",
+            "Time", "Record", "Winning hold times", "Optimal hold", "Margin"
+        );
+        for race_report in race_reports {
+            let winning_hold_times = race_report
+                .get_winning_hold_times()
+                .iter()
+                .map(|hold_times| format!("{}..={}", hold_times.start(), hold_times.end()))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            println!(
+                "{:>16} | {:>16} | {:>33} | {:>16} | {:>16}",
+                race_report.get_time(),
+                race_report.get_record_distance(),
+                winning_hold_times,
+                race_report.get_optimal_hold_time(),
+                race_report.get_margin()
+            );
+        }
+        println!();
+    }
+
+    for (parse_mode, parse_mode_name) in [
+        (RaceParseMode::SeparateRaces, "separate races"),
+        (RaceParseMode::KernedRace, "kerned race"),
+    ] {
+        match race_statistics.get_race_record_break_product(parse_mode) {
+            Ok(product) => println!(
+                "Product of record break opportunities({}): {}",
+                parse_mode_name, product
+            ),
+            Err(error) => eprintln!(
+                "Unable to count record break opportunities({}): {}",
+                parse_mode_name, error
+            ),
+        }
+    }
 
     Ok(())
 }
@@ -1,2 +1,7 @@
+#[cfg(test)]
+extern crate test_support;
+
+mod race_model;
 mod race_statistics;
-pub use race_statistics::RaceStatistics;
+pub use race_model::{ChargePhase, RaceModel, RaceReport};
+pub use race_statistics::{RaceParseError, RaceParseMode, RaceStatistics};
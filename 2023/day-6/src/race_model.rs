@@ -0,0 +1,398 @@
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChargePhase {
+    // None charges for as long as the button is held.
+    duration: Option<u64>,
+    acceleration: u64,
+}
+
+impl ChargePhase {
+    pub fn new(duration: Option<u64>, acceleration: u64) -> Self {
+        ChargePhase {
+            duration,
+            acceleration,
+        }
+    }
+}
+
+// Holding the button runs through the charging phases in order, each one
+// adding its acceleration to the boat speed for every millisecond spent in it.
+// Once the phases run out the speed stays the same, and it never exceeds the
+// maximum speed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaceModel {
+    charging_phases: Vec<ChargePhase>,
+    max_speed: Option<u64>,
+}
+
+impl Default for RaceModel {
+    fn default() -> Self {
+        RaceModel::new(vec![ChargePhase::new(None, 1)], None)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaceReport {
+    time: u64,
+    record_distance: u64,
+    winning_hold_times: Vec<RangeInclusive<u64>>,
+    optimal_hold_time: u64,
+    optimal_distance: u128,
+}
+
+impl RaceReport {
+    pub fn get_time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn get_record_distance(&self) -> u64 {
+        self.record_distance
+    }
+
+    pub fn get_winning_hold_times(&self) -> &[RangeInclusive<u64>] {
+        &self.winning_hold_times
+    }
+
+    pub fn get_record_break_count(&self) -> u64 {
+        self.winning_hold_times
+            .iter()
+            .map(|hold_times| hold_times.end() - hold_times.start() + 1)
+            .sum()
+    }
+
+    pub fn get_optimal_hold_time(&self) -> u64 {
+        self.optimal_hold_time
+    }
+
+    pub fn get_optimal_distance(&self) -> u128 {
+        self.optimal_distance
+    }
+
+    pub fn get_margin(&self) -> i128 {
+        self.optimal_distance as i128 - self.record_distance as i128
+    }
+}
+
+// Hold times from `start` to `end` during which the speed grows linearly
+// from `start_speed` by `acceleration` per millisecond.
+#[derive(Debug)]
+struct ChargeSegment {
+    start: u64,
+    end: u64,
+    start_speed: u128,
+    acceleration: u128,
+}
+
+impl RaceModel {
+    pub fn new(charging_phases: Vec<ChargePhase>, max_speed: Option<u64>) -> Self {
+        RaceModel {
+            charging_phases,
+            max_speed,
+        }
+    }
+
+    pub fn get_speed(&self, hold_time: u64) -> u128 {
+        let mut speed: u128 = 0;
+        let mut remaining_hold_time = hold_time;
+
+        for phase in &self.charging_phases {
+            let phase_hold_time = phase.duration.map_or(remaining_hold_time, |duration| {
+                duration.min(remaining_hold_time)
+            });
+            speed = speed.saturating_add(phase.acceleration as u128 * phase_hold_time as u128);
+            remaining_hold_time -= phase_hold_time;
+        }
+
+        self.cap_speed(speed)
+    }
+
+    pub fn get_distance(&self, time: u64, hold_time: u64) -> u128 {
+        self.get_speed(hold_time)
+            .saturating_mul(time.saturating_sub(hold_time) as u128)
+    }
+
+    pub fn report(&self, time: u64, record_distance: u64) -> RaceReport {
+        let mut winning_hold_times: Vec<RangeInclusive<u64>> = Vec::new();
+        let mut optimal_hold_time: u64 = 0;
+        let mut optimal_distance: u128 = 0;
+
+        for segment in self.charge_segments(time) {
+            let distance = |hold_time: u64| {
+                segment
+                    .speed(hold_time, self.max_speed)
+                    .saturating_mul((time - hold_time) as u128)
+            };
+
+            // The distance over a segment is a concave function of the hold
+            // time, so it rises up to its peak and falls after it.
+            let peak_hold_time = partition_point(segment.start, segment.end, |hold_time| {
+                distance(hold_time) < distance(hold_time + 1)
+            });
+            let peak_distance = distance(peak_hold_time);
+
+            if peak_distance > optimal_distance {
+                optimal_hold_time = peak_hold_time;
+                optimal_distance = peak_distance;
+            }
+
+            if peak_distance <= record_distance as u128 {
+                continue;
+            }
+
+            let first_winning_hold_time =
+                partition_point(segment.start, peak_hold_time, |hold_time| {
+                    distance(hold_time) <= record_distance as u128
+                });
+            let last_winning_hold_time =
+                partition_point(peak_hold_time, segment.end, |hold_time| {
+                    distance(hold_time + 1) > record_distance as u128
+                });
+
+            match winning_hold_times.last_mut() {
+                Some(previous) if *previous.end() + 1 >= first_winning_hold_time => {
+                    *previous = *previous.start()..=last_winning_hold_time.max(*previous.end());
+                }
+                _ => winning_hold_times.push(first_winning_hold_time..=last_winning_hold_time),
+            }
+        }
+
+        RaceReport {
+            time,
+            record_distance,
+            winning_hold_times,
+            optimal_hold_time,
+            optimal_distance,
+        }
+    }
+
+    fn cap_speed(&self, speed: u128) -> u128 {
+        self.max_speed
+            .map_or(speed, |max_speed| speed.min(max_speed as u128))
+    }
+
+    // Splits the hold times 0..=time into segments of linear speed growth,
+    // ordered by hold time.
+    fn charge_segments(&self, time: u64) -> Vec<ChargeSegment> {
+        let mut segments: Vec<ChargeSegment> = Vec::new();
+        let mut start: u64 = 0;
+        let mut start_speed: u128 = 0;
+
+        for phase in &self.charging_phases {
+            let end = phase
+                .duration
+                .map_or(time, |duration| start.saturating_add(duration).min(time));
+            let acceleration = phase.acceleration as u128;
+
+            self.push_capped_segment(&mut segments, start, end, start_speed, acceleration);
+            start_speed =
+                start_speed.saturating_add(acceleration.saturating_mul((end - start) as u128));
+            start = end;
+
+            if start == time {
+                break;
+            }
+        }
+
+        self.push_capped_segment(&mut segments, start, time, start_speed, 0);
+        segments
+    }
+
+    fn push_capped_segment(
+        &self,
+        segments: &mut Vec<ChargeSegment>,
+        start: u64,
+        end: u64,
+        start_speed: u128,
+        acceleration: u128,
+    ) {
+        let capped_start_speed = self.cap_speed(start_speed);
+
+        // Past the hold time reaching the maximum speed the speed stays the same.
+        let cap_hold_time = match self.max_speed {
+            Some(max_speed) if acceleration > 0 && capped_start_speed < max_speed as u128 => {
+                let cap_hold_time =
+                    start as u128 + (max_speed as u128 - capped_start_speed).div_ceil(acceleration);
+                (cap_hold_time <= end as u128).then_some(cap_hold_time as u64)
+            }
+            Some(max_speed) if capped_start_speed >= max_speed as u128 => Some(start),
+            _ => None,
+        };
+
+        match cap_hold_time {
+            Some(cap_hold_time) => {
+                if start < cap_hold_time {
+                    segments.push(ChargeSegment {
+                        start,
+                        end: cap_hold_time - 1,
+                        start_speed: capped_start_speed,
+                        acceleration,
+                    });
+                }
+                segments.push(ChargeSegment {
+                    start: cap_hold_time,
+                    end,
+                    start_speed: self.cap_speed(u128::MAX),
+                    acceleration: 0,
+                });
+            }
+            None => segments.push(ChargeSegment {
+                start,
+                end,
+                start_speed: capped_start_speed,
+                acceleration,
+            }),
+        }
+    }
+}
+
+impl ChargeSegment {
+    fn speed(&self, hold_time: u64, max_speed: Option<u64>) -> u128 {
+        let speed = self.start_speed.saturating_add(
+            self.acceleration
+                .saturating_mul((hold_time - self.start) as u128),
+        );
+
+        max_speed.map_or(speed, |max_speed| speed.min(max_speed as u128))
+    }
+}
+
+// First value in start..=end for which the predicate is false, or end if the
+// predicate holds everywhere before it. The predicate must be true up to some
+// point and false after it.
+fn partition_point(start: u64, end: u64, predicate: impl Fn(u64) -> bool) -> u64 {
+    let (mut low, mut high) = (start, end);
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::XorShift;
+
+    fn brute_force_report(race_model: &RaceModel, time: u64, record_distance: u64) -> RaceReport {
+        let mut winning_hold_times: Vec<RangeInclusive<u64>> = Vec::new();
+        let mut optimal_hold_time: u64 = 0;
+        let mut optimal_distance: u128 = 0;
+
+        for hold_time in 0..=time {
+            let distance = race_model.get_distance(time, hold_time);
+
+            if distance > optimal_distance {
+                optimal_hold_time = hold_time;
+                optimal_distance = distance;
+            }
+
+            if distance > record_distance as u128 {
+                match winning_hold_times.last_mut() {
+                    Some(previous) if *previous.end() + 1 == hold_time => {
+                        *previous = *previous.start()..=hold_time;
+                    }
+                    _ => winning_hold_times.push(hold_time..=hold_time),
+                }
+            }
+        }
+
+        RaceReport {
+            time,
+            record_distance,
+            winning_hold_times,
+            optimal_hold_time,
+            optimal_distance,
+        }
+    }
+
+    #[test]
+    fn default_model_report() {
+        let report = RaceModel::default().report(30, 200);
+
+        assert_eq!(&[11..=19], report.get_winning_hold_times());
+        assert_eq!(9, report.get_record_break_count());
+        assert_eq!(15, report.get_optimal_hold_time());
+        assert_eq!(225, report.get_optimal_distance());
+        assert_eq!(25, report.get_margin());
+    }
+
+    #[test]
+    fn unbeatable_record_report() {
+        let report = RaceModel::default().report(7, 100);
+
+        assert!(report.get_winning_hold_times().is_empty());
+        assert_eq!(0, report.get_record_break_count());
+        assert_eq!(3, report.get_optimal_hold_time());
+        assert_eq!(-88, report.get_margin());
+    }
+
+    #[test]
+    fn acceleration_and_max_speed() {
+        let race_model = RaceModel::new(vec![ChargePhase::new(None, 3)], Some(12));
+
+        assert_eq!(9, race_model.get_speed(3));
+        assert_eq!(12, race_model.get_speed(4));
+        assert_eq!(12, race_model.get_speed(10));
+        assert_eq!(
+            brute_force_report(&race_model, 20, 100),
+            race_model.report(20, 100)
+        );
+    }
+
+    #[test]
+    fn multi_phase_charging() {
+        let race_model = RaceModel::new(
+            vec![ChargePhase::new(Some(2), 5), ChargePhase::new(Some(3), 1)],
+            None,
+        );
+
+        assert_eq!(5, race_model.get_speed(1));
+        assert_eq!(10, race_model.get_speed(2));
+        assert_eq!(13, race_model.get_speed(5));
+        assert_eq!(13, race_model.get_speed(9));
+        assert_eq!(
+            brute_force_report(&race_model, 12, 60),
+            race_model.report(12, 60)
+        );
+    }
+
+    #[test]
+    fn report_matches_brute_force_on_random_models() {
+        let mut random = XorShift::default();
+
+        for _ in 0..2000 {
+            let charging_phases = (0..random.next(4))
+                .map(|_| {
+                    let duration = match random.next(3) {
+                        0 => None,
+                        _ => Some(random.next(20)),
+                    };
+                    ChargePhase::new(duration, random.next(6))
+                })
+                .collect();
+            let max_speed = match random.next(2) {
+                0 => None,
+                _ => Some(random.next(40)),
+            };
+            let race_model = RaceModel::new(charging_phases, max_speed);
+            let time = random.next(60);
+            let record_distance = random.next(400);
+
+            assert_eq!(
+                brute_force_report(&race_model, time, record_distance),
+                race_model.report(time, record_distance),
+                "{:?} time {} record {}",
+                race_model,
+                time,
+                record_distance
+            );
+        }
+    }
+}
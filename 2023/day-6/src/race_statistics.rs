@@ -1,6 +1,46 @@
+use std::fmt;
+
+use crate::race_model::{RaceModel, RaceReport};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RaceParseMode {
+    SeparateRaces,
+    KernedRace,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RaceParseError {
+    MissingRow(&'static str),
+    InvalidNumber { row: &'static str, value: String },
+    MismatchedRaceCount { times: usize, distances: usize },
+    KernedValueOverflow(&'static str),
+}
+
+impl fmt::Display for RaceParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaceParseError::MissingRow(row) => write!(f, "missing `{}:` row", row),
+            RaceParseError::InvalidNumber { row, value } => {
+                write!(f, "`{}:` row contains invalid number `{}`", row, value)
+            }
+            RaceParseError::MismatchedRaceCount { times, distances } => write!(
+                f,
+                "{} race times do not match {} record distances",
+                times, distances
+            ),
+            RaceParseError::KernedValueOverflow(row) => {
+                write!(f, "kerned `{}:` value does not fit into 64 bits", row)
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RaceStatistics {
     record_table: Vec<RecordTable>,
+    // A kerned value that overflows only rules out the kerned race, the
+    // separate races can still be used.
+    kerned_record: Result<RecordTable, RaceParseError>,
 }
 
 #[derive(Debug)]
@@ -10,89 +50,332 @@ struct RecordTable {
 }
 
 impl RaceStatistics {
-    pub fn parse(record_table: &str, ignore_white_space: bool) -> Self {
-        let race_time_data = record_table.split('\n').nth(0).unwrap();
-        let race_distance_data = record_table.split('\n').nth(1).unwrap();
-
-        let race_times: Vec<u64>;
-        let race_distances: Vec<u64>;
-        if ignore_white_space {
-            race_times = vec![race_time_data
-                .split_whitespace()
-                .filter(|string| string.parse::<u64>().is_ok())
-                .collect::<String>()
-                .parse::<u64>()
-                .unwrap()];
-
-            race_distances = vec![race_distance_data
-                .split_whitespace()
-                .filter(|string| string.parse::<u64>().is_ok())
-                .collect::<String>()
-                .parse::<u64>()
-                .unwrap()];
-        } else {
-            race_times = race_time_data
-                .split_whitespace()
-                .filter_map(|string| string.parse::<u64>().ok())
-                .collect();
-
-            race_distances = race_distance_data
-                .split_whitespace()
-                .filter_map(|string| string.parse::<u64>().ok())
-                .collect();
+    pub fn parse(record_table: &str) -> Result<Self, RaceParseError> {
+        const TIME_LABEL: &str = "Time";
+        const DISTANCE_LABEL: &str = "Distance";
+
+        let mut rows = record_table
+            .split('\n')
+            .filter(|row| !row.trim().is_empty());
+        let race_times = RaceStatistics::parse_row(rows.next(), TIME_LABEL)?;
+        let race_distances = RaceStatistics::parse_row(rows.next(), DISTANCE_LABEL)?;
+
+        if race_times.len() != race_distances.len() {
+            return Err(RaceParseError::MismatchedRaceCount {
+                times: race_times.len(),
+                distances: race_distances.len(),
+            });
         }
 
-        // NOTE: assuming race_times.len() == race_distances.len()
-        let mut record_table: Vec<RecordTable> = Vec::new();
-        for i in 0..race_times.len() {
-            record_table.push(RecordTable {
-                record_time: race_times[i],
-                record_distance: race_distances[i],
+        let kerned_record = RaceStatistics::kern(&race_times, TIME_LABEL).and_then(|record_time| {
+            Ok(RecordTable {
+                record_time,
+                record_distance: RaceStatistics::kern(&race_distances, DISTANCE_LABEL)?,
             })
-        }
+        });
 
-        RaceStatistics { record_table }
+        let record_table = race_times
+            .iter()
+            .zip(race_distances.iter())
+            .map(|(race_time, race_distance)| RecordTable {
+                record_time: race_time.parse().unwrap(),
+                record_distance: race_distance.parse().unwrap(),
+            })
+            .collect();
+
+        Ok(RaceStatistics {
+            record_table,
+            kerned_record,
+        })
+    }
+
+    // Returns the digits of every number in the labelled row, keeping them as
+    // written so they can also be kerned into a single number.
+    fn parse_row<'a>(
+        row: Option<&'a str>,
+        label: &'static str,
+    ) -> Result<Vec<&'a str>, RaceParseError> {
+        let values = row
+            .and_then(|row| row.trim().strip_prefix(label))
+            .and_then(|row| row.strip_prefix(':'))
+            .ok_or(RaceParseError::MissingRow(label))?;
+
+        values
+            .split_whitespace()
+            .map(|value| match value.parse::<u64>() {
+                Ok(_) => Ok(value),
+                Err(_) => Err(RaceParseError::InvalidNumber {
+                    row: label,
+                    value: value.to_string(),
+                }),
+            })
+            .collect()
+    }
+
+    fn kern(values: &[&str], label: &'static str) -> Result<u64, RaceParseError> {
+        values
+            .concat()
+            .parse::<u64>()
+            .map_err(|_| RaceParseError::KernedValueOverflow(label))
     }
 
-    pub fn get_race_record_break_product(&self) -> u64 {
-        self.record_table
+    pub fn get_race_record_break_product(
+        &self,
+        parse_mode: RaceParseMode,
+    ) -> Result<u64, RaceParseError> {
+        Ok(self
+            .get_records(parse_mode)?
             .iter()
             .map(|entry| {
                 RaceStatistics::get_record_break_count(entry.record_time, entry.record_distance)
             })
-            .product()
+            .product())
+    }
+
+    pub fn report(
+        &self,
+        parse_mode: RaceParseMode,
+        race_model: &RaceModel,
+    ) -> Result<Vec<RaceReport>, RaceParseError> {
+        Ok(self
+            .get_records(parse_mode)?
+            .iter()
+            .map(|entry| race_model.report(entry.record_time, entry.record_distance))
+            .collect())
     }
 
+    fn get_records(&self, parse_mode: RaceParseMode) -> Result<&[RecordTable], RaceParseError> {
+        match parse_mode {
+            RaceParseMode::SeparateRaces => Ok(&self.record_table),
+            RaceParseMode::KernedRace => self
+                .kerned_record
+                .as_ref()
+                .map(std::slice::from_ref)
+                .map_err(Clone::clone),
+        }
+    }
+
+    // Holding the button for h ms breaks the record when h * (time - h) > distance.
+    // The winning hold times lie strictly between the roots of
+    // h^2 - time * h + distance = 0, which are found with an integer square
+    // root and then nudged onto the first winning hold time.
     fn get_record_break_count(time: u64, distance: u64) -> u64 {
-        let distance_1 =
-            ((time as f64 + ((time.pow(2) - 4 * distance) as f64).sqrt()) / 2_f64).ceil() as u64;
-        let distance_2 =
-            ((time as f64 - ((time.pow(2) - 4 * distance) as f64).sqrt()) / 2_f64).ceil() as u64;
+        let time = time as u128;
+        let distance = distance as u128;
+        let beats_record = |hold_time: u128| hold_time * (time - hold_time) > distance;
+
+        if time * time <= 4 * distance {
+            return 0;
+        }
+
+        let discriminant_root = (time * time - 4 * distance).isqrt();
+        let mut first_winning_hold_time = (time - discriminant_root) / 2;
 
-        (distance_2..distance_1).count() as u64
+        while first_winning_hold_time > 0 && beats_record(first_winning_hold_time - 1) {
+            first_winning_hold_time -= 1;
+        }
+        while first_winning_hold_time <= time / 2 && !beats_record(first_winning_hold_time) {
+            first_winning_hold_time += 1;
+        }
+
+        if first_winning_hold_time > time / 2 {
+            return 0;
+        }
+
+        let last_winning_hold_time = time - first_winning_hold_time;
+        (last_winning_hold_time - first_winning_hold_time + 1) as u64
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::XorShift;
 
     fn assert_race_record_break_product(
         expected_race_record_brake_product: u64,
         record_table: &str,
-        ignore_white_space: bool,
+        parse_mode: RaceParseMode,
     ) {
         assert_eq!(
             expected_race_record_brake_product,
-            RaceStatistics::parse(record_table, ignore_white_space).get_race_record_break_product()
+            RaceStatistics::parse(record_table)
+                .unwrap()
+                .get_race_record_break_product(parse_mode)
+                .unwrap()
         );
     }
 
     #[test]
     fn one_race_possible_record_breaks() {
-        assert_race_record_break_product(4, "Time: 7\nDistance: 9\n", false);
-        assert_race_record_break_product(8, "Time: 15\nDistance: 40\n", false);
-        assert_race_record_break_product(320, "Time: 7  15   30\nDistance: 9  40  200\n", false);
-        assert_race_record_break_product(71503, "Time: 7  15   30\nDistance: 9  40  200\n", true);
+        assert_race_record_break_product(4, "Time: 7\nDistance: 9\n", RaceParseMode::SeparateRaces);
+        assert_race_record_break_product(
+            8,
+            "Time: 15\nDistance: 40\n",
+            RaceParseMode::SeparateRaces,
+        );
+        assert_race_record_break_product(
+            288,
+            "Time: 7  15   30\nDistance: 9  40  200\n",
+            RaceParseMode::SeparateRaces,
+        );
+        assert_race_record_break_product(
+            71503,
+            "Time: 7  15   30\nDistance: 9  40  200\n",
+            RaceParseMode::KernedRace,
+        );
+    }
+
+    #[test]
+    fn kerned_race_keeps_leading_zeros() {
+        assert_race_record_break_product(
+            14,
+            "Time: 1 03\nDistance: 2 600\n",
+            RaceParseMode::KernedRace,
+        );
+    }
+
+    #[test]
+    fn malformed_record_tables() {
+        assert_eq!(
+            RaceParseError::MissingRow("Time"),
+            RaceStatistics::parse("Distance: 9\nTime: 7\n").unwrap_err()
+        );
+        assert_eq!(
+            RaceParseError::MissingRow("Distance"),
+            RaceStatistics::parse("Time: 7\n").unwrap_err()
+        );
+        assert_eq!(
+            RaceParseError::InvalidNumber {
+                row: "Distance",
+                value: "9x".to_string()
+            },
+            RaceStatistics::parse("Time: 7\nDistance: 9x\n").unwrap_err()
+        );
+        assert_eq!(
+            RaceParseError::MismatchedRaceCount {
+                times: 2,
+                distances: 1
+            },
+            RaceStatistics::parse("Time: 7 15\nDistance: 9\n").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn kerned_overflow_only_fails_the_kerned_race() {
+        let race_statistics =
+            RaceStatistics::parse("Time: 7 00000000000000000015\nDistance: 9 40\n").unwrap();
+        assert_eq!(
+            Ok(32),
+            race_statistics.get_race_record_break_product(RaceParseMode::SeparateRaces)
+        );
+        assert_eq!(
+            Err(RaceParseError::KernedValueOverflow("Time")),
+            race_statistics.get_race_record_break_product(RaceParseMode::KernedRace)
+        );
+
+        let race_statistics =
+            RaceStatistics::parse("Time: 7 15\nDistance: 9 00000000000000000040\n").unwrap();
+        assert_eq!(
+            2,
+            race_statistics
+                .report(RaceParseMode::SeparateRaces, &RaceModel::default())
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            Err(RaceParseError::KernedValueOverflow("Distance")),
+            race_statistics
+                .report(RaceParseMode::KernedRace, &RaceModel::default())
+                .map(|report| report.len())
+        );
+    }
+
+    #[test]
+    fn default_model_report_matches_record_break_count() {
+        let race_statistics =
+            RaceStatistics::parse("Time: 7  15   30\nDistance: 9  40  200\n").unwrap();
+
+        for parse_mode in [RaceParseMode::SeparateRaces, RaceParseMode::KernedRace] {
+            let report = race_statistics
+                .report(parse_mode, &RaceModel::default())
+                .unwrap();
+
+            assert_eq!(
+                race_statistics
+                    .get_race_record_break_product(parse_mode)
+                    .unwrap(),
+                report
+                    .iter()
+                    .map(|race_report| race_report.get_record_break_count())
+                    .product::<u64>()
+            );
+        }
+    }
+
+    #[test]
+    fn perfect_square_boundaries_do_not_break_the_record() {
+        assert_eq!(9, RaceStatistics::get_record_break_count(30, 200));
+        assert_eq!(1, RaceStatistics::get_record_break_count(4, 3));
+        assert_eq!(0, RaceStatistics::get_record_break_count(4, 4));
+        assert_eq!(3, RaceStatistics::get_record_break_count(6, 5));
+    }
+
+    #[test]
+    fn unbeatable_records() {
+        assert_eq!(0, RaceStatistics::get_record_break_count(0, 0));
+        assert_eq!(0, RaceStatistics::get_record_break_count(7, 12));
+        assert_eq!(0, RaceStatistics::get_record_break_count(7, 100));
+        assert_eq!(0, RaceStatistics::get_record_break_count(3, u64::MAX));
+        assert_race_record_break_product(
+            0,
+            "Time: 7 5\nDistance: 9 100\n",
+            RaceParseMode::SeparateRaces,
+        );
+    }
+
+    #[test]
+    fn huge_kerned_races() {
+        assert_eq!(
+            u64::MAX - 1,
+            RaceStatistics::get_record_break_count(u64::MAX, 0)
+        );
+        assert_eq!(
+            u64::MAX - 3,
+            RaceStatistics::get_record_break_count(u64::MAX, u64::MAX - 1)
+        );
+        assert_eq!(
+            999_999_999_999_999_999,
+            RaceStatistics::get_record_break_count(1_000_000_000_000_000_000, 0)
+        );
+    }
+
+    fn brute_force_record_break_count(time: u64, distance: u64) -> u64 {
+        (0..=time)
+            .filter(|hold_time| hold_time * (time - hold_time) > distance)
+            .count() as u64
+    }
+
+    #[test]
+    fn record_break_count_matches_brute_force() {
+        let mut random = XorShift::default();
+
+        for time in 0..100 {
+            for distance in 0..(time * time / 4 + 2) {
+                assert_eq!(
+                    brute_force_record_break_count(time, distance),
+                    RaceStatistics::get_record_break_count(time, distance)
+                );
+            }
+        }
+
+        for _ in 0..1000 {
+            let time = random.next(100_000);
+            let distance = random.next(time * time / 4 + 2);
+            assert_eq!(
+                brute_force_record_break_count(time, distance),
+                RaceStatistics::get_record_break_count(time, distance)
+            );
+        }
     }
 }
@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate criterion;
+extern crate day_7;
+extern crate test_support;
+
+use criterion::{BatchSize, Criterion};
+use day_7::{CardGame, Hand, Rules};
+use test_support::XorShift;
+
+const LABELS: [char; 13] = [
+    'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+];
+const HAND_COUNT: usize = 1_000_000;
+
+fn generate_hands(game: &CardGame) -> Vec<Hand> {
+    let mut random = XorShift::default();
+
+    (0..HAND_COUNT)
+        .map(|_| {
+            let hand: String = (0..5)
+                .map(|_| LABELS[random.next(LABELS.len() as u64) as usize])
+                .collect();
+            Hand::parse(&hand, game).unwrap()
+        })
+        .collect()
+}
+
+fn hand_ranking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rank one million hands");
+    group.sample_size(10);
+
+    for (rules, rules_name) in [(Rules::Standard, "standard"), (Rules::Jokers, "jokers")] {
+        let hands = generate_hands(&CardGame::from(rules));
+
+        // Compares the hand category weights and then the card weights one by
+        // one, the ordering the sort key has to reproduce.
+        group.bench_function(format!("card weights({})", rules_name), |b| {
+            b.iter_batched(
+                || hands.clone(),
+                |mut hands| {
+                    day_7::rank_by_card_weights(&mut hands);
+                    hands
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function(format!("sort key({})", rules_name), |b| {
+            b.iter_batched(
+                || hands.clone(),
+                |mut hands| {
+                    day_7::rank_by_sort_key(&mut hands);
+                    hands
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, hand_ranking);
+criterion_main!(benches);
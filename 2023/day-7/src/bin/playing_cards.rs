@@ -2,16 +2,54 @@ use std::fs::File;
 use std::io::prelude::*;
 
 extern crate day_7;
-use day_7::CamelCards;
+use day_7::{CamelCards, Rules, StandingsFormat};
 
 fn main() -> std::io::Result<()> {
+    let standings_format = std::env::args().find_map(|argument| {
+        argument
+            .strip_prefix("--standings")
+            .map(|format| match format {
+                "" | "=text" => StandingsFormat::Text,
+                "=csv" => StandingsFormat::Csv,
+                "=json" => StandingsFormat::Json,
+                _ => {
+                    eprintln!(
+                        "Unknown standings format `{}`",
+                        format.trim_start_matches('=')
+                    );
+                    std::process::exit(1);
+                }
+            })
+    });
     let mut came_cards_input_file =
         File::open("/home/lpaulic/Documents/github/advent-of-code/2023/day-7/src/bin/data/input")?;
     let mut came_cards_input = String::new();
 
     came_cards_input_file.read_to_string(&mut came_cards_input)?;
-    let camel_cards = CamelCards::new(&came_cards_input);
-    println!("Total winings: {}", camel_cards.get_total_winnings());
+    for (rules, rules_name) in [
+        (Rules::Standard, "standard rules"),
+        (Rules::Jokers, "joker rules"),
+    ] {
+        let camel_cards = match CamelCards::new(&came_cards_input, rules) {
+            Ok(camel_cards) => camel_cards,
+            Err(error) => {
+                eprintln!("Unable to parse the list of hands: {}", error);
+                std::process::exit(1);
+            }
+        };
+
+        if let Some(standings_format) = standings_format {
+            println!("Standings({}):", rules_name);
+            print!("{}", camel_cards.export_standings(standings_format));
+            println!();
+        }
+
+        println!(
+            "Total winings({}): {}",
+            rules_name,
+            camel_cards.get_total_winnings()
+        );
+    }
 
     Ok(())
 }
@@ -1,25 +1,156 @@
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::fmt;
+
+use card_game::CardGame;
+use standings::{self, Standing, StandingsFormat};
+
+// The standard deck and hand categories, see CardGame for other variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rules {
+    // J is a jack, ranked between T and Q.
+    Standard,
+    // J is a joker, the weakest card, which acts as whatever card makes the
+    // hand type strongest.
+    Jokers,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CamelCardsError {
+    UnknownCardLabel {
+        line: usize,
+        label: char,
+    },
+    WrongHandLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    MissingBid {
+        line: usize,
+    },
+    InvalidBid {
+        line: usize,
+        bid: String,
+    },
+    UnexpectedField {
+        line: usize,
+        field: String,
+    },
+}
 
-#[derive(Debug)]
+impl fmt::Display for CamelCardsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CamelCardsError::UnknownCardLabel { line, label } => {
+                write!(f, "line {}: unknown card label `{}`", line, label)
+            }
+            CamelCardsError::WrongHandLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: hand has {} cards, expected {}",
+                line, found, expected
+            ),
+            CamelCardsError::MissingBid { line } => write!(f, "line {}: missing bid", line),
+            CamelCardsError::InvalidBid { line, bid } => {
+                write!(f, "line {}: bid `{}` is not a number", line, bid)
+            }
+            CamelCardsError::UnexpectedField { line, field } => {
+                write!(f, "line {}: unexpected `{}` after the bid", line, field)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct CamelCards {
+    game: CardGame,
     hand_bid_list: Vec<HandBidMap>,
 }
 
 impl CamelCards {
-    pub fn new(list_of_hands: &str) -> Self {
-        let mut hand_bid_list: Vec<HandBidMap> = list_of_hands
-            .trim()
-            .split('\n')
-            .map(|line| HandBidMap {
-                hand: Hand::from(line.split_whitespace().next().unwrap()),
-                bid: line.split_whitespace().nth(1).unwrap().parse().unwrap(),
-            })
-            .collect();
+    pub fn new(list_of_hands: &str, rules: Rules) -> Result<Self, CamelCardsError> {
+        CamelCards::with_game(list_of_hands, CardGame::from(rules))
+    }
 
-        hand_bid_list.sort();
+    pub fn with_game(list_of_hands: &str, game: CardGame) -> Result<Self, CamelCardsError> {
+        let mut camel_cards = CamelCards::parse_unranked(list_of_hands, game)?;
+        camel_cards.rank_by_sort_key();
 
-        CamelCards { hand_bid_list }
+        Ok(camel_cards)
+    }
+
+    // Keeps the hands in input order, every other method expects them to be
+    // ranked.
+    fn parse_unranked(list_of_hands: &str, game: CardGame) -> Result<Self, CamelCardsError> {
+        let mut hand_bid_list: Vec<HandBidMap> = Vec::new();
+
+        for (index, line) in list_of_hands
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+        {
+            let line_number = index + 1;
+            let mut columns = line.split_whitespace();
+
+            let hand = Hand::parse(columns.next().unwrap(), &game).map_err(
+                |UnknownCardLabel(label)| CamelCardsError::UnknownCardLabel {
+                    line: line_number,
+                    label,
+                },
+            )?;
+
+            if hand.cards.len() != game.get_hand_size() {
+                return Err(CamelCardsError::WrongHandLength {
+                    line: line_number,
+                    expected: game.get_hand_size(),
+                    found: hand.cards.len(),
+                });
+            }
+
+            let bid = match columns.next() {
+                Some(bid) => bid.parse().map_err(|_| CamelCardsError::InvalidBid {
+                    line: line_number,
+                    bid: bid.to_string(),
+                })?,
+                None => return Err(CamelCardsError::MissingBid { line: line_number }),
+            };
+
+            if let Some(field) = columns.next() {
+                return Err(CamelCardsError::UnexpectedField {
+                    line: line_number,
+                    field: field.to_string(),
+                });
+            }
+
+            hand_bid_list.push(HandBidMap { hand, bid });
+        }
+
+        Ok(CamelCards {
+            game,
+            hand_bid_list,
+        })
+    }
+
+    // Ranks the hands by their packed sort keys, falling back to comparing
+    // them card by card when they are too long to be packed.
+    fn rank_by_sort_key(&mut self) {
+        if self
+            .hand_bid_list
+            .iter()
+            .all(|hand_bid| hand_bid.hand.sort_key.is_some())
+        {
+            self.hand_bid_list
+                .sort_unstable_by_key(|hand_bid| (hand_bid.hand.sort_key, hand_bid.bid));
+        } else {
+            self.rank_by_card_weights();
+        }
+    }
+
+    // Ranks the hands by comparing their types and then their cards one by one.
+    fn rank_by_card_weights(&mut self) {
+        self.hand_bid_list.sort();
     }
 
     pub fn get_total_winnings(&self) -> u64 {
@@ -29,335 +160,512 @@ impl CamelCards {
             .map(|(index, hand_bid)| hand_bid.bid * (index as u64 + 1))
             .sum()
     }
+
+    pub fn standings(&self) -> Vec<Standing> {
+        let labels = |cards: &[usize]| -> String {
+            cards
+                .iter()
+                .map(|&weight| self.game.card_label(weight))
+                .collect()
+        };
+
+        self.hand_bid_list
+            .iter()
+            .enumerate()
+            .map(|(index, hand_bid)| {
+                let hand = &hand_bid.hand;
+                Standing::new(
+                    index as u64 + 1,
+                    labels(&hand.cards),
+                    self.game.category(hand.hand_type).get_name().to_string(),
+                    labels(&self.game.best_substitution(&hand.cards, hand.hand_type)),
+                    hand_bid.bid,
+                )
+            })
+            .collect()
+    }
+
+    pub fn export_standings(&self, format: StandingsFormat) -> String {
+        standings::export(&self.standings(), format)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct HandBidMap {
     hand: Hand,
     bid: u64,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct Hand {
-    hand_type: HandType,
-    cards: Vec<Card>,
+// Hands are ordered by their hand category weight and then by their card
+// weights one by one; the sort key only summarises those two.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hand {
+    hand_type: usize,
+    cards: Vec<usize>,
+    sort_key: Option<u32>,
 }
 
-impl From<&str> for Hand {
-    fn from(s: &str) -> Self {
-        let cards: Vec<Card> = s.chars().map(Card::from).collect();
-        Hand {
-            hand_type: Hand::calculate_hand_type(&cards),
-            cards,
-        }
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownCardLabel(pub char);
+
+impl fmt::Display for UnknownCardLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown card label `{}`", self.0)
     }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Hand {
+    pub fn parse(s: &str, game: &CardGame) -> Result<Self, UnknownCardLabel> {
+        let cards = s
+            .chars()
+            .map(|c| game.card_weight(c).ok_or(UnknownCardLabel(c)))
+            .collect::<Result<Vec<usize>, UnknownCardLabel>>()?;
+
+        let hand_type = game.categorise(&cards);
+        Ok(Hand {
+            sort_key: Hand::calculate_sort_key(game, hand_type, &cards),
+            hand_type,
+            cards,
+        })
     }
-}
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.hand_type.cmp(&other.hand_type) {
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Less => Ordering::Less,
-            Ordering::Equal => {
-                // NOTE: assuming they are the same length without check
-                for i in 0..self.cards.len() {
-                    let order = self.cards[i].cmp(&other.cards[i]);
-                    if order.is_lt() || order.is_gt() {
-                        return order;
-                    }
-                }
-                Ordering::Equal
-            }
+    // Packs the hand type into the top bits and every card weight into the
+    // bits below it, first card first, so comparing keys orders hands the same
+    // way as comparing them type first and then card by card. With the
+    // standard game hands of more than seven cards do not fit into 32 bits.
+    fn calculate_sort_key(game: &CardGame, hand_type: usize, cards: &[usize]) -> Option<u32> {
+        let card_bits = game.card_bits();
+
+        if game.category_bits() + cards.len() * card_bits > u32::BITS as usize {
+            return None;
         }
+
+        Some(cards.iter().fold(hand_type as u32, |key, &weight| {
+            (key << card_bits) | weight as u32
+        }))
     }
 }
 
-impl Hand {
-    fn calculate_hand_type(cards: &[Card]) -> HandType {
-        let mut type_count_map = HashMap::from([
-            (CardLabel::A(14), 0),
-            (CardLabel::K(13), 0),
-            (CardLabel::Q(12), 0),
-            (CardLabel::T(10), 0),
-            (CardLabel::Nine(9), 0),
-            (CardLabel::Eight(8), 0),
-            (CardLabel::Seven(7), 0),
-            (CardLabel::Six(6), 0),
-            (CardLabel::Five(5), 0),
-            (CardLabel::Four(4), 0),
-            (CardLabel::Three(3), 0),
-            (CardLabel::Two(2), 0),
-            (CardLabel::J(1), 0),
-        ]);
-
-        cards
-            .iter()
-            .for_each(|card| *type_count_map.get_mut(&card.label).unwrap() += 1);
-
-        let hand_type: HandType;
-        if type_count_map.iter().any(|type_count| *type_count.1 == 5) {
-            hand_type = HandType::FiveOfAKind(7);
-        } else if type_count_map.iter().any(|type_count| *type_count.1 == 4) {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &1
-                || type_count_map.get(&CardLabel::J(1)).unwrap() == &4
-            {
-                hand_type = HandType::FiveOfAKind(7);
-            } else {
-                hand_type = HandType::FourOfAKind(6);
-            }
-        } else if type_count_map
-            .iter()
-            .filter(|type_count| *type_count.1 == 2)
-            .count()
-            == 1
-            && type_count_map
-                .iter()
-                .filter(|type_count| *type_count.1 == 3)
-                .count()
-                == 1
-        {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &2
-                || type_count_map.get(&CardLabel::J(1)).unwrap() == &3
-            {
-                hand_type = HandType::FiveOfAKind(7);
-            } else {
-                hand_type = HandType::FullHouse(5);
-            }
-        } else if type_count_map.iter().any(|type_count| *type_count.1 == 3) {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &3
-                || type_count_map.get(&CardLabel::J(1)).unwrap() == &1
-            {
-                hand_type = HandType::FourOfAKind(6);
-            } else {
-                hand_type = HandType::ThreeOfAKind(4);
-            }
-        } else if type_count_map
-            .iter()
-            .filter(|type_count| *type_count.1 == 2)
-            .count()
-            == 2
-        {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &1 {
-                hand_type = HandType::FullHouse(5);
-            } else if type_count_map.get(&CardLabel::J(1)).unwrap() == &2 {
-                hand_type = HandType::FourOfAKind(6);
-            } else {
-                hand_type = HandType::TwoPair(3);
-            }
-        } else if type_count_map.iter().any(|type_count| *type_count.1 == 2) {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &1
-                || type_count_map.get(&CardLabel::J(1)).unwrap() == &2
-            {
-                hand_type = HandType::ThreeOfAKind(4);
-            } else {
-                hand_type = HandType::OnePair(2);
-            }
-        } else {
-            if type_count_map.get(&CardLabel::J(1)).unwrap() == &1 {
-                hand_type = HandType::OnePair(2);
-            } else {
-                hand_type = HandType::HighCard(1);
-            }
-        }
-
-        hand_type
+// Ranks the hands weakest first by their packed sort keys, falling back to
+// comparing them card by card when any of them is too long to be packed.
+pub fn rank_by_sort_key(hands: &mut [Hand]) {
+    if hands.iter().all(|hand| hand.sort_key.is_some()) {
+        hands.sort_unstable_by_key(|hand| hand.sort_key);
+    } else {
+        rank_by_card_weights(hands);
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum HandType {
-    FiveOfAKind(u8),
-    FourOfAKind(u8),
-    FullHouse(u8),
-    ThreeOfAKind(u8),
-    TwoPair(u8),
-    OnePair(u8),
-    HighCard(u8),
+// Ranks the hands weakest first by their hand category weights and then by
+// their card weights one by one.
+pub fn rank_by_card_weights(hands: &mut [Hand]) {
+    hands.sort_unstable();
 }
 
-impl PartialOrd for HandType {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use card_game::HandCategory;
+    use std::collections::HashMap;
+    use test_support::XorShift;
+
+    fn assert_camel_card_total_winnings(expected_total_winnings: u64, list_of_hands: &str) {
+        assert_camel_card_total_winnings_with_rules(
+            expected_total_winnings,
+            list_of_hands,
+            Rules::Jokers,
+        );
     }
-}
 
-impl Ord for HandType {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let self_weight = match *self {
-            HandType::FiveOfAKind(weight) => weight,
-            HandType::FourOfAKind(weight) => weight,
-            HandType::FullHouse(weight) => weight,
-            HandType::ThreeOfAKind(weight) => weight,
-            HandType::TwoPair(weight) => weight,
-            HandType::OnePair(weight) => weight,
-            HandType::HighCard(weight) => weight,
-        };
+    fn assert_camel_card_total_winnings_with_rules(
+        expected_total_winnings: u64,
+        list_of_hands: &str,
+        rules: Rules,
+    ) {
+        assert_eq!(
+            expected_total_winnings,
+            CamelCards::new(list_of_hands, rules)
+                .unwrap()
+                .get_total_winnings()
+        );
+    }
 
-        let other_weight = match *other {
-            HandType::FiveOfAKind(weight) => weight,
-            HandType::FourOfAKind(weight) => weight,
-            HandType::FullHouse(weight) => weight,
-            HandType::ThreeOfAKind(weight) => weight,
-            HandType::TwoPair(weight) => weight,
-            HandType::OnePair(weight) => weight,
-            HandType::HighCard(weight) => weight,
-        };
+    #[test]
+    fn camel_cards_one_hand() {
+        assert_camel_card_total_winnings(1, "34562 1");
+        assert_camel_card_total_winnings(2, "34562 2");
+    }
 
-        self_weight.cmp(&other_weight)
+    #[test]
+    fn camel_cards_two_hands_same_type() {
+        assert_camel_card_total_winnings(5, "34562 1\n34562 2");
     }
-}
 
-#[derive(Debug, PartialEq, Eq)]
-struct Card {
-    label: CardLabel,
-}
+    #[test]
+    fn camel_cards_two_hands_different_type() {
+        assert_camel_card_total_winnings(4, "AAAAA 1\n34562 2");
+        assert_camel_card_total_winnings(4, "AA776 1\nAA775 2");
+    }
+
+    #[test]
+    fn camel_cards_five_hands_different_types() {
+        assert_camel_card_total_winnings(
+            5905,
+            "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n",
+        );
+    }
 
-impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    #[test]
+    fn camel_cards_standard_rules() {
+        assert_camel_card_total_winnings_with_rules(
+            6440,
+            "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n",
+            Rules::Standard,
+        );
     }
-}
 
-impl Ord for Card {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.label.cmp(&other.label)
+    #[test]
+    fn camel_cards_jack_ranking_depends_on_rules() {
+        assert_camel_card_total_winnings_with_rules(4, "2345J 1\n2345T 2", Rules::Standard);
+        assert_camel_card_total_winnings_with_rules(4, "2345J 1\n2345T 2", Rules::Jokers);
+        assert_camel_card_total_winnings_with_rules(5, "JKKK2 1\nQQQ32 2", Rules::Standard);
+        assert_camel_card_total_winnings_with_rules(4, "JKKK2 1\nQQQ32 2", Rules::Jokers);
+        assert_camel_card_total_winnings_with_rules(4, "JJJJJ 1\n22222 2", Rules::Standard);
+        assert_camel_card_total_winnings_with_rules(5, "JJJJJ 1\n22222 2", Rules::Jokers);
     }
-}
 
-impl From<char> for Card {
-    fn from(c: char) -> Self {
-        Card {
-            label: if c == 'A' || c == 'a' {
-                CardLabel::A(14)
-            } else if c == 'K' || c == 'k' {
-                CardLabel::K(13)
-            } else if c == 'Q' || c == 'q' {
-                CardLabel::Q(12)
-            } else if c == 'J' || c == 'j' {
-                CardLabel::J(1)
-            } else if c == 'T' || c == 't' {
-                CardLabel::T(10)
-            } else if c == '9' {
-                CardLabel::Nine(9)
-            } else if c == '8' {
-                CardLabel::Eight(8)
-            } else if c == '7' {
-                CardLabel::Seven(7)
-            } else if c == '6' {
-                CardLabel::Six(6)
-            } else if c == '5' {
-                CardLabel::Five(5)
-            } else if c == '4' {
-                CardLabel::Four(4)
-            } else if c == '3' {
-                CardLabel::Three(3)
-            } else {
-                CardLabel::Two(2)
-            },
+    const LABELS: [char; 13] = [
+        'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+    ];
+
+    // Classifies a five card hand by looking its exact group sizes up, with no
+    // joker handling at all, into the weights of the standard categories.
+    fn oracle_hand_type(labels: &[usize; 5]) -> usize {
+        let mut groups = [0; 5];
+        for (group, label) in groups.iter_mut().zip(labels) {
+            *group = labels.iter().filter(|&other| other == label).count();
+        }
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Every card of a group of n reports the size n, e.g. a full house is
+        // [3, 3, 3, 2, 2].
+        match groups {
+            [5, 5, 5, 5, 5] => 7,
+            [4, 4, 4, 4, 1] => 6,
+            [3, 3, 3, 2, 2] => 5,
+            [3, 3, 3, 1, 1] => 4,
+            [2, 2, 2, 2, 1] => 3,
+            [2, 2, 1, 1, 1] => 2,
+            [1, 1, 1, 1, 1] => 1,
+            _ => unreachable!("five cards cannot make {:?}", groups),
         }
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum CardLabel {
-    A(u8),
-    K(u8),
-    Q(u8),
-    T(u8),
-    Nine(u8),
-    Eight(u8),
-    Seven(u8),
-    Six(u8),
-    Five(u8),
-    Four(u8),
-    Three(u8),
-    Two(u8),
-    J(u8),
-}
+    // Tries every possible replacement for every joker independently.
+    fn oracle_joker_hand_type(labels: [usize; 5], position: usize) -> usize {
+        if position == labels.len() {
+            return oracle_hand_type(&labels);
+        }
+        if LABELS[labels[position]] != 'J' {
+            return oracle_joker_hand_type(labels, position + 1);
+        }
 
-impl PartialOrd for CardLabel {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+        (0..LABELS.len())
+            .filter(|&label| LABELS[label] != 'J')
+            .map(|label| {
+                let mut substituted = labels;
+                substituted[position] = label;
+                oracle_joker_hand_type(substituted, position + 1)
+            })
+            .max()
+            .unwrap()
     }
-}
 
-impl Ord for CardLabel {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let self_weight = match *self {
-            CardLabel::A(weight) => weight,
-            CardLabel::K(weight) => weight,
-            CardLabel::Q(weight) => weight,
-            CardLabel::T(weight) => weight,
-            CardLabel::Nine(weight) => weight,
-            CardLabel::Eight(weight) => weight,
-            CardLabel::Seven(weight) => weight,
-            CardLabel::Six(weight) => weight,
-            CardLabel::Five(weight) => weight,
-            CardLabel::Four(weight) => weight,
-            CardLabel::Three(weight) => weight,
-            CardLabel::Two(weight) => weight,
-            CardLabel::J(weight) => weight,
-        };
+    #[test]
+    fn hand_type_matches_brute_force_for_every_hand() {
+        // The brute force only depends on which labels are in the hand, so it
+        // is run once per multiset rather than once per ordering.
+        let mut joker_hand_types: HashMap<[usize; 5], usize> = HashMap::new();
+        let standard_game = CardGame::from(Rules::Standard);
+        let joker_game = CardGame::from(Rules::Jokers);
+
+        for index in 0..LABELS.len().pow(5) {
+            let mut labels = [0; 5];
+            let mut remainder = index;
+            for label in labels.iter_mut() {
+                *label = remainder % LABELS.len();
+                remainder /= LABELS.len();
+            }
+            let hand: String = labels.iter().map(|&label| LABELS[label]).collect();
+
+            assert_eq!(
+                oracle_hand_type(&labels),
+                Hand::parse(&hand, &standard_game).unwrap().hand_type,
+                "{hand} with standard rules"
+            );
+            let mut sorted_labels = labels;
+            sorted_labels.sort_unstable();
+            assert_eq!(
+                *joker_hand_types
+                    .entry(sorted_labels)
+                    .or_insert_with(|| oracle_joker_hand_type(sorted_labels, 0)),
+                Hand::parse(&hand, &joker_game).unwrap().hand_type,
+                "{hand} with joker rules"
+            );
+        }
+    }
 
-        let other_weight = match *other {
-            CardLabel::A(weight) => weight,
-            CardLabel::K(weight) => weight,
-            CardLabel::Q(weight) => weight,
-            CardLabel::T(weight) => weight,
-            CardLabel::Nine(weight) => weight,
-            CardLabel::Eight(weight) => weight,
-            CardLabel::Seven(weight) => weight,
-            CardLabel::Six(weight) => weight,
-            CardLabel::Five(weight) => weight,
-            CardLabel::Four(weight) => weight,
-            CardLabel::Three(weight) => weight,
-            CardLabel::Two(weight) => weight,
-            CardLabel::J(weight) => weight,
+    #[test]
+    fn hand_type_of_other_hand_sizes() {
+        let assert_hand_type = |expected: &str, hand: &str, rules: Rules| {
+            let game = CardGame::from(rules);
+            let hand_type = Hand::parse(hand, &game).unwrap().hand_type;
+            assert_eq!(expected, game.category(hand_type).get_name(), "{hand}");
         };
 
-        self_weight.cmp(&other_weight)
+        assert_hand_type("High card", "", Rules::Jokers);
+        assert_hand_type("High card", "J", Rules::Jokers);
+        assert_hand_type("One pair", "2J", Rules::Jokers);
+        assert_hand_type("Three of a kind", "JJJ", Rules::Jokers);
+        assert_hand_type("Five of a kind", "JJJJJJ", Rules::Jokers);
+        assert_hand_type("Full house", "AAAKKK", Rules::Standard);
+        assert_hand_type("Four of a kind", "AAAJKK", Rules::Jokers);
+        assert_hand_type("Two pair", "AAKKQQT", Rules::Standard);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn assert_camel_cards_error(expected_error: CamelCardsError, list_of_hands: &str) {
+        assert_eq!(
+            expected_error,
+            CamelCards::new(list_of_hands, Rules::Standard).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn camel_cards_unknown_card_label() {
+        assert_camel_cards_error(
+            CamelCardsError::UnknownCardLabel {
+                line: 2,
+                label: '1',
+            },
+            "32T3K 765\nT1JJ5 684",
+        );
+        assert_camel_cards_error(
+            CamelCardsError::UnknownCardLabel {
+                line: 1,
+                label: 'X',
+            },
+            "XXXXX 1",
+        );
+    }
+
+    #[test]
+    fn camel_cards_wrong_hand_length() {
+        assert_camel_cards_error(
+            CamelCardsError::WrongHandLength {
+                line: 3,
+                expected: 5,
+                found: 4,
+            },
+            "32T3K 765\nT55J5 684\nKK67 28",
+        );
+        assert_camel_cards_error(
+            CamelCardsError::WrongHandLength {
+                line: 1,
+                expected: 5,
+                found: 4,
+            },
+            "3456 1",
+        );
+    }
+
+    #[test]
+    fn camel_cards_unexpected_field() {
+        assert_camel_cards_error(
+            CamelCardsError::UnexpectedField {
+                line: 1,
+                field: String::from("garbage"),
+            },
+            "32T3K 765 garbage",
+        );
+        assert_camel_cards_error(
+            CamelCardsError::UnexpectedField {
+                line: 2,
+                field: String::from("684"),
+            },
+            "32T3K 765\nT55J5 684 684",
+        );
+    }
+
+    #[test]
+    fn camel_cards_missing_or_invalid_bid() {
+        assert_camel_cards_error(CamelCardsError::MissingBid { line: 2 }, "32T3K 765\nT55J5");
+        assert_camel_cards_error(
+            CamelCardsError::InvalidBid {
+                line: 1,
+                bid: String::from("-765"),
+            },
+            "32T3K -765",
+        );
+        assert_camel_cards_error(
+            CamelCardsError::InvalidBid {
+                line: 1,
+                bid: String::from("bid"),
+            },
+            "32T3K bid",
+        );
+    }
+
+    #[test]
+    fn camel_cards_error_line_counts_blank_lines() {
+        assert_camel_cards_error(
+            CamelCardsError::MissingBid { line: 4 },
+            "\n32T3K 765\n\nT55J5\n",
+        );
+    }
+
+    #[test]
+    fn camel_cards_standings() {
+        let camel_cards = CamelCards::new(
+            "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\nJJJJJ 7\n",
+            Rules::Jokers,
+        )
+        .unwrap();
+        let standings = camel_cards.standings();
+
+        let summary: Vec<(u64, &str, &str, &str, u64, u64)> = standings
+            .iter()
+            .map(|standing| {
+                (
+                    standing.get_rank(),
+                    standing.get_hand(),
+                    standing.get_hand_type(),
+                    standing.get_best_substitution(),
+                    standing.get_bid(),
+                    standing.get_winnings(),
+                )
+            })
+            .collect();
 
-    fn assert_camel_card_total_winnings(expected_total_winnings: u64, list_of_hands: &str) {
         assert_eq!(
-            expected_total_winnings,
-            CamelCards::new(list_of_hands).get_total_winnings()
+            vec![
+                (1, "32T3K", "One pair", "32T3K", 765, 765),
+                (2, "KK677", "Two pair", "KK677", 28, 56),
+                (3, "T55J5", "Four of a kind", "T5555", 684, 2052),
+                (4, "QQQJA", "Four of a kind", "QQQQA", 483, 1932),
+                (5, "KTJJT", "Four of a kind", "KTTTT", 220, 1100),
+                (6, "JJJJJ", "Five of a kind", "AAAAA", 7, 42),
+            ],
+            summary
+        );
+        assert_eq!(
+            camel_cards.get_total_winnings(),
+            standings.iter().map(Standing::get_winnings).sum()
         );
     }
 
     #[test]
-    fn camel_cards_one_hand() {
-        assert_camel_card_total_winnings(1, "3456 1");
-        assert_camel_card_total_winnings(2, "3456 2");
+    fn camel_cards_standings_substitution_tie() {
+        let camel_cards = CamelCards::new("2KJ2K 1", Rules::Jokers).unwrap();
+        assert_eq!("2KK2K", camel_cards.standings()[0].get_best_substitution());
+
+        let camel_cards = CamelCards::new("2KJ2K 1", Rules::Standard).unwrap();
+        assert_eq!("2KJ2K", camel_cards.standings()[0].get_best_substitution());
+        assert_eq!("Two pair", camel_cards.standings()[0].get_hand_type());
+    }
+
+    fn generate_hands(random: &mut XorShift, hand_count: usize, hand_size: usize) -> String {
+        (0..hand_count)
+            .map(|_| {
+                let hand: String = (0..hand_size)
+                    .map(|_| LABELS[random.next(LABELS.len() as u64) as usize])
+                    .collect();
+                // Few distinct bids, so that equal hands with equal bids occur.
+                format!("{} {}\n", hand, random.next(4) + 1)
+            })
+            .collect()
     }
 
     #[test]
-    fn camel_cards_two_hands_same_type() {
-        assert_camel_card_total_winnings(5, "3456 1\n3456 2");
+    fn sort_key_ranking_matches_card_weight_ranking() {
+        let mut random = XorShift::default();
+
+        for (hand_count, hand_size) in [(2000, 5), (2000, 3), (500, 2), (300, 7), (300, 8)] {
+            let list_of_hands = generate_hands(&mut random, hand_count, hand_size);
+
+            for rules in [Rules::Standard, Rules::Jokers] {
+                let game = CardGame::from(rules).with_hand_size(hand_size).unwrap();
+                let by_sort_key = CamelCards::with_game(&list_of_hands, game.clone()).unwrap();
+                let mut by_card_weights = by_sort_key.clone();
+                by_card_weights.rank_by_card_weights();
+
+                assert_eq!(by_card_weights.standings(), by_sort_key.standings());
+
+                let mut hands: Vec<Hand> = list_of_hands
+                    .lines()
+                    .map(|line| Hand::parse(&line[..hand_size], &game).unwrap())
+                    .collect();
+                let mut hands_by_card_weights = hands.clone();
+                super::rank_by_sort_key(&mut hands);
+                super::rank_by_card_weights(&mut hands_by_card_weights);
+
+                assert_eq!(hands_by_card_weights, hands);
+            }
+        }
     }
 
     #[test]
-    fn camel_cards_two_hands_different_type() {
-        assert_camel_card_total_winnings(4, "AAAAA 1\n3456 2");
-        assert_camel_card_total_winnings(4, "AA776 1\nAA775 2");
+    fn sort_key_packs_type_above_cards() {
+        let standard_game = CardGame::from(Rules::Standard);
+        let joker_game = CardGame::from(Rules::Jokers);
+
+        let hand = Hand::parse("A2J3T", &standard_game).unwrap();
+        assert_eq!(Some(0x1D_1A29), hand.sort_key);
+
+        let hand = Hand::parse("A2J3T", &joker_game).unwrap();
+        assert_eq!(Some(0x2D_213A), hand.sort_key);
+
+        let hand = Hand::parse("AAAAAAA", &standard_game).unwrap();
+        assert_eq!(Some(0x7DDD_DDDD), hand.sort_key);
+
+        let hand = Hand::parse("AAAAAAAA", &standard_game).unwrap();
+        assert_eq!(None, hand.sort_key);
     }
 
     #[test]
-    fn camel_cards_five_hands_different_types() {
-        assert_camel_card_total_winnings(
-            5905,
-            "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n",
+    fn camel_cards_variant_game() {
+        let mut categories = CardGame::standard_categories();
+        // Between the full house and four of a kind.
+        categories.insert(5, HandCategory::new("Three pair", &[2, 2, 2]));
+        categories.push(HandCategory::new("Six of a kind", &[6]));
+        let game = CardGame::new("23456789TJQKAZ", categories)
+            .unwrap()
+            .with_joker('Z')
+            .unwrap()
+            .with_hand_size(6)
+            .unwrap();
+
+        let camel_cards = CamelCards::with_game(
+            "AAAAAA 1\nZ22334 2\nZZZZZZ 3\nKK4455 4\nAAAAK2 5\n23456A 6",
+            game,
+        )
+        .unwrap();
+        let standings = camel_cards.standings();
+        let summary: Vec<(&str, &str)> = standings
+            .iter()
+            .map(|standing| (standing.get_hand(), standing.get_hand_type()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("23456A", "High card"),
+                ("Z22334", "Three pair"),
+                ("KK4455", "Three pair"),
+                ("AAAAK2", "Four of a kind"),
+                ("ZZZZZZ", "Six of a kind"),
+                ("AAAAAA", "Six of a kind"),
+            ],
+            summary
         );
+        assert_eq!("422334", standings[1].get_best_substitution());
     }
 }
@@ -0,0 +1,471 @@
+use std::fmt;
+
+use camel_cards::Rules;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CardGameError {
+    EmptyLabels,
+    DuplicateLabel(char),
+    UnknownJoker(char),
+    OnlyJokerLabel(char),
+    EmptyHand,
+    EmptyCatalogue,
+    MissingCatchAllCategory(String),
+}
+
+impl fmt::Display for CardGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardGameError::EmptyLabels => write!(f, "there are no card labels"),
+            CardGameError::DuplicateLabel(label) => {
+                write!(f, "card label `{}` appears more than once", label)
+            }
+            CardGameError::UnknownJoker(label) => {
+                write!(f, "joker `{}` is not one of the card labels", label)
+            }
+            CardGameError::OnlyJokerLabel(label) => {
+                write!(f, "joker `{}` is the only card label", label)
+            }
+            CardGameError::EmptyHand => write!(f, "hands have to hold at least one card"),
+            CardGameError::EmptyCatalogue => write!(f, "there are no hand categories"),
+            CardGameError::MissingCatchAllCategory(name) => write!(
+                f,
+                "weakest hand category `{}` has to accept every hand",
+                name
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandCategory {
+    name: String,
+    groups: Vec<usize>,
+}
+
+impl HandCategory {
+    // The groups are the sizes of same label groups a hand needs at least,
+    // e.g. [3, 2] for a full house. One hand group may make up several of
+    // them, so five of a kind is a full house too and four of a kind is two
+    // pair; categories are picked strongest first.
+    pub fn new(name: &str, groups: &[usize]) -> Self {
+        let mut groups: Vec<usize> = groups.iter().copied().filter(|&size| size > 0).collect();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        HandCategory {
+            name: name.to_string(),
+            groups,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_groups(&self) -> &[usize] {
+        &self.groups
+    }
+
+    // Number of jokers needed to turn the hand into this category, next to
+    // the number of cards each hand group has to grow to for it, in count
+    // signature order. Every required group is tried on every hand group; a
+    // hand of only jokers puts all of them on a single new label instead.
+    fn missing_cards(&self, count_signature: &[(usize, usize)]) -> (usize, Vec<usize>) {
+        if count_signature.is_empty() {
+            return (self.groups.iter().sum(), Vec::new());
+        }
+
+        let mut group_sizes = vec![0; count_signature.len()];
+        let mut best_fit = (usize::MAX, Vec::new());
+        self.fit_groups(0, count_signature, &mut group_sizes, &mut best_fit);
+
+        best_fit
+    }
+
+    fn fit_groups(
+        &self,
+        index: usize,
+        count_signature: &[(usize, usize)],
+        group_sizes: &mut [usize],
+        best_fit: &mut (usize, Vec<usize>),
+    ) {
+        let Some(&size) = self.groups.get(index) else {
+            let missing_cards = group_sizes
+                .iter()
+                .zip(count_signature)
+                .map(|(&group_size, &(count, _))| group_size.saturating_sub(count))
+                .sum();
+            if missing_cards < best_fit.0 {
+                *best_fit = (missing_cards, group_sizes.to_vec());
+            }
+            return;
+        };
+
+        for hand_group in 0..group_sizes.len() {
+            if best_fit.0 == 0 {
+                return;
+            }
+
+            // Hand groups of the same count holding the same required cards
+            // are interchangeable, only the stronger label is tried.
+            let interchangeable = (0..hand_group).any(|other| {
+                count_signature[other].0 == count_signature[hand_group].0
+                    && group_sizes[other] == group_sizes[hand_group]
+            });
+            if interchangeable {
+                continue;
+            }
+
+            group_sizes[hand_group] += size;
+            self.fit_groups(index + 1, count_signature, group_sizes, best_fit);
+            group_sizes[hand_group] -= size;
+        }
+    }
+}
+
+// Card labels and hand categories are both listed weakest first; a card or
+// category weight is its position in that list, starting at one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardGame {
+    labels: Vec<char>,
+    joker: Option<char>,
+    hand_size: usize,
+    categories: Vec<HandCategory>,
+}
+
+impl CardGame {
+    pub fn new(labels: &str, categories: Vec<HandCategory>) -> Result<Self, CardGameError> {
+        let labels: Vec<char> = labels.chars().collect();
+        if labels.is_empty() {
+            return Err(CardGameError::EmptyLabels);
+        }
+        if let Some(index) =
+            (1..labels.len()).find(|&index| labels[..index].contains(&labels[index]))
+        {
+            return Err(CardGameError::DuplicateLabel(labels[index]));
+        }
+
+        match categories.first() {
+            None => return Err(CardGameError::EmptyCatalogue),
+            Some(weakest) if !weakest.groups.is_empty() => {
+                return Err(CardGameError::MissingCatchAllCategory(weakest.name.clone()))
+            }
+            Some(_) => (),
+        }
+
+        Ok(CardGame {
+            labels,
+            joker: None,
+            hand_size: 5,
+            categories,
+        })
+    }
+
+    // Makes the label a joker: the weakest card, which stands in for whatever
+    // card gives the strongest hand category, so it needs another label to
+    // stand in for.
+    pub fn with_joker(mut self, joker: char) -> Result<Self, CardGameError> {
+        let index = self
+            .labels
+            .iter()
+            .position(|&label| label == joker)
+            .ok_or(CardGameError::UnknownJoker(joker))?;
+        if self.labels.len() == 1 {
+            return Err(CardGameError::OnlyJokerLabel(joker));
+        }
+
+        self.labels.remove(index);
+        self.labels.insert(0, joker);
+        self.joker = Some(joker);
+
+        Ok(self)
+    }
+
+    // Number of cards every hand has to hold, five unless changed here.
+    pub fn with_hand_size(mut self, hand_size: usize) -> Result<Self, CardGameError> {
+        if hand_size == 0 {
+            return Err(CardGameError::EmptyHand);
+        }
+        self.hand_size = hand_size;
+
+        Ok(self)
+    }
+
+    pub fn standard_labels() -> &'static str {
+        "23456789TJQKA"
+    }
+
+    pub fn standard_categories() -> Vec<HandCategory> {
+        vec![
+            HandCategory::new("High card", &[]),
+            HandCategory::new("One pair", &[2]),
+            HandCategory::new("Two pair", &[2, 2]),
+            HandCategory::new("Three of a kind", &[3]),
+            HandCategory::new("Full house", &[3, 2]),
+            HandCategory::new("Four of a kind", &[4]),
+            HandCategory::new("Five of a kind", &[5]),
+        ]
+    }
+
+    pub fn get_labels(&self) -> &[char] {
+        &self.labels
+    }
+
+    pub fn get_joker(&self) -> Option<char> {
+        self.joker
+    }
+
+    pub fn get_hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    pub fn get_categories(&self) -> &[HandCategory] {
+        &self.categories
+    }
+
+    // Labels are case insensitive unless the game uses both cases.
+    pub(crate) fn card_weight(&self, label: char) -> Option<usize> {
+        self.labels
+            .iter()
+            .position(|&other| other == label)
+            .or_else(|| {
+                self.labels
+                    .iter()
+                    .position(|&other| other == label.to_ascii_uppercase())
+            })
+            .map(|index| index + 1)
+    }
+
+    pub(crate) fn card_label(&self, weight: usize) -> char {
+        self.labels[weight - 1]
+    }
+
+    pub(crate) fn category(&self, weight: usize) -> &HandCategory {
+        &self.categories[weight - 1]
+    }
+
+    pub(crate) fn card_bits(&self) -> usize {
+        CardGame::bits_for(self.labels.len())
+    }
+
+    pub(crate) fn category_bits(&self) -> usize {
+        CardGame::bits_for(self.categories.len())
+    }
+
+    fn bits_for(max_weight: usize) -> usize {
+        (usize::BITS - max_weight.leading_zeros()) as usize
+    }
+
+    fn is_joker(&self, weight: usize) -> bool {
+        self.joker == Some(self.card_label(weight))
+    }
+
+    // Sizes of the same label groups without jokers, largest first and the
+    // stronger label first among equal sizes, next to the number of jokers.
+    fn count_signature(&self, cards: &[usize]) -> (Vec<(usize, usize)>, usize) {
+        let mut label_counts = vec![0; self.labels.len() + 1];
+        let mut joker_count = 0;
+        for &weight in cards {
+            if self.is_joker(weight) {
+                joker_count += 1;
+            } else {
+                label_counts[weight] += 1;
+            }
+        }
+
+        let mut count_signature: Vec<(usize, usize)> = label_counts
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, count)| count > 0)
+            .map(|(weight, count)| (count, weight))
+            .collect();
+        count_signature.sort_unstable_by(|a, b| b.cmp(a));
+
+        (count_signature, joker_count)
+    }
+
+    pub(crate) fn categorise(&self, cards: &[usize]) -> usize {
+        let (count_signature, joker_count) = self.count_signature(cards);
+
+        // The weakest category needs no groups, so some category always fits.
+        self.categories
+            .iter()
+            .rposition(|category| category.missing_cards(&count_signature).0 <= joker_count)
+            .unwrap()
+            + 1
+    }
+
+    // Replaces every joker with the card it stands in for: jokers first grow
+    // the hand groups the hand category needs, any that are left over join
+    // the largest group, or the strongest label in a hand of only jokers.
+    pub(crate) fn best_substitution(&self, cards: &[usize], category_weight: usize) -> Vec<usize> {
+        let (count_signature, _) = self.count_signature(cards);
+        let (_, group_sizes) = self
+            .category(category_weight)
+            .missing_cards(&count_signature);
+
+        let mut substitutes = group_sizes
+            .iter()
+            .zip(&count_signature)
+            .flat_map(|(&group_size, &(count, weight))| (count..group_size).map(move |_| weight));
+        let leftover_weight = count_signature
+            .first()
+            .map_or_else(|| self.strongest_weight(), |&(_, weight)| weight);
+
+        cards
+            .iter()
+            .map(|&weight| {
+                if self.is_joker(weight) {
+                    substitutes.next().unwrap_or(leftover_weight)
+                } else {
+                    weight
+                }
+            })
+            .collect()
+    }
+
+    // Games always have a label besides the joker.
+    fn strongest_weight(&self) -> usize {
+        (1..=self.labels.len())
+            .rev()
+            .find(|&weight| !self.is_joker(weight))
+            .unwrap()
+    }
+}
+
+impl From<Rules> for CardGame {
+    fn from(rules: Rules) -> Self {
+        let game =
+            CardGame::new(CardGame::standard_labels(), CardGame::standard_categories()).unwrap();
+
+        match rules {
+            Rules::Standard => game,
+            Rules::Jokers => game.with_joker('J').unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_game_rejects_invalid_configuration() {
+        assert_eq!(
+            Err(CardGameError::DuplicateLabel('3')),
+            CardGame::new("2343", CardGame::standard_categories())
+        );
+        assert_eq!(
+            Err(CardGameError::EmptyCatalogue),
+            CardGame::new("234", Vec::new())
+        );
+        assert_eq!(
+            Err(CardGameError::MissingCatchAllCategory(String::from(
+                "One pair"
+            ))),
+            CardGame::new("234", vec![HandCategory::new("One pair", &[2])])
+        );
+        assert_eq!(
+            Err(CardGameError::EmptyLabels),
+            CardGame::new("", CardGame::standard_categories())
+        );
+        assert_eq!(
+            Err(CardGameError::OnlyJokerLabel('J')),
+            CardGame::new("J", CardGame::standard_categories())
+                .unwrap()
+                .with_joker('J')
+        );
+        assert_eq!(
+            Err(CardGameError::EmptyHand),
+            CardGame::new("234", CardGame::standard_categories())
+                .unwrap()
+                .with_hand_size(0)
+        );
+        assert_eq!(
+            Err(CardGameError::UnknownJoker('J')),
+            CardGame::new("234", CardGame::standard_categories())
+                .unwrap()
+                .with_joker('J')
+        );
+    }
+
+    #[test]
+    fn card_game_joker_is_the_weakest_label() {
+        let game = CardGame::from(Rules::Jokers);
+        assert_eq!(Some('J'), game.get_joker());
+        assert_eq!(&['J', '2', '3'], &game.get_labels()[..3]);
+        assert_eq!(Some(1), game.card_weight('J'));
+        assert_eq!(Some(1), game.card_weight('j'));
+        assert_eq!(Some(13), game.card_weight('A'));
+        assert_eq!(None, game.card_weight('1'));
+    }
+
+    #[test]
+    fn hand_category_groups_are_sorted() {
+        let category = HandCategory::new("Full house", &[2, 0, 3]);
+        assert_eq!("Full house", category.get_name());
+        assert_eq!(&[3, 2], category.get_groups());
+    }
+
+    fn weights(game: &CardGame, hand: &str) -> Vec<usize> {
+        hand.chars()
+            .map(|label| game.card_weight(label).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn categorise_without_the_stronger_categories() {
+        let categorise = |category: HandCategory, hand: &str| {
+            let game = CardGame::new(
+                CardGame::standard_labels(),
+                vec![HandCategory::new("High card", &[]), category],
+            )
+            .unwrap()
+            .with_joker('J')
+            .unwrap();
+
+            game.category(game.categorise(&weights(&game, hand)))
+                .get_name()
+                .to_string()
+        };
+        let full_house = || HandCategory::new("Full house", &[3, 2]);
+        let two_pair = || HandCategory::new("Two pair", &[2, 2]);
+
+        assert_eq!("Full house", categorise(full_house(), "AAAAA"));
+        assert_eq!("Full house", categorise(full_house(), "AAAJK"));
+        assert_eq!("Full house", categorise(full_house(), "JJJJJ"));
+        assert_eq!("High card", categorise(full_house(), "AAAAK"));
+        assert_eq!("Two pair", categorise(two_pair(), "AAAAK"));
+        assert_eq!("Two pair", categorise(two_pair(), "AAJKQ"));
+        assert_eq!("High card", categorise(two_pair(), "AJKQ2"));
+    }
+
+    #[test]
+    fn best_substitution_grows_the_needed_groups() {
+        let game = CardGame::new(
+            "JABC",
+            vec![
+                HandCategory::new("Nothing", &[]),
+                HandCategory::new("Two triples", &[3, 3]),
+            ],
+        )
+        .unwrap()
+        .with_joker('J')
+        .unwrap();
+        let substitute = |hand: &str| -> String {
+            let cards = weights(&game, hand);
+            let category_weight = game.categorise(&cards);
+            assert_eq!("Two triples", game.category(category_weight).get_name());
+
+            game.best_substitution(&cards, category_weight)
+                .into_iter()
+                .map(|weight| game.card_label(weight))
+                .collect()
+        };
+
+        // Six aces make up both triples.
+        assert_eq!("AAAAAA", substitute("AAJJJJ"));
+        assert_eq!("ABBBAA", substitute("ABJJJJ"));
+        assert_eq!("CCCCCC", substitute("JJJJJJ"));
+    }
+}
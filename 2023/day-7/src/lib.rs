@@ -1,2 +1,12 @@
+#[cfg(test)]
+extern crate test_support;
+
 mod camel_cards;
-pub use camel_cards::CamelCards;
+mod card_game;
+mod standings;
+pub use camel_cards::{
+    rank_by_card_weights, rank_by_sort_key, CamelCards, CamelCardsError, Hand, Rules,
+    UnknownCardLabel,
+};
+pub use card_game::{CardGame, CardGameError, HandCategory};
+pub use standings::{Standing, StandingsFormat};
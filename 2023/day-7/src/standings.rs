@@ -0,0 +1,233 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StandingsFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standing {
+    rank: u64,
+    hand: String,
+    hand_type: String,
+    best_substitution: String,
+    bid: u64,
+    winnings: u64,
+}
+
+impl Standing {
+    pub(crate) fn new(
+        rank: u64,
+        hand: String,
+        hand_type: String,
+        best_substitution: String,
+        bid: u64,
+    ) -> Self {
+        Standing {
+            rank,
+            hand,
+            hand_type,
+            best_substitution,
+            bid,
+            winnings: rank * bid,
+        }
+    }
+
+    pub fn get_rank(&self) -> u64 {
+        self.rank
+    }
+
+    pub fn get_hand(&self) -> &str {
+        &self.hand
+    }
+
+    pub fn get_hand_type(&self) -> &str {
+        &self.hand_type
+    }
+
+    pub fn get_best_substitution(&self) -> &str {
+        &self.best_substitution
+    }
+
+    pub fn get_bid(&self) -> u64 {
+        self.bid
+    }
+
+    pub fn get_winnings(&self) -> u64 {
+        self.winnings
+    }
+}
+
+// Hands are made of arbitrary card labels and hand types are arbitrary
+// category names, so both are quoted and escaped for CSV and JSON.
+pub(crate) fn export(standings: &[Standing], format: StandingsFormat) -> String {
+    let mut output = String::new();
+
+    match format {
+        StandingsFormat::Text => {
+            output.push_str(&format!(
+                "{:>6} | {:<6} | {:<15} | {:<12} | {:>6} | {:>10}\n",
+                "Rank", "Hand", "Type", "Substitution", "Bid", "Winnings"
+            ));
+            for standing in standings {
+                output.push_str(&format!(
+                    "{:>6} | {:<6} | {:<15} | {:<12} | {:>6} | {:>10}\n",
+                    standing.rank,
+                    standing.hand,
+                    standing.hand_type,
+                    standing.best_substitution,
+                    standing.bid,
+                    standing.winnings
+                ));
+            }
+        }
+        StandingsFormat::Csv => {
+            output.push_str("rank,hand,hand_type,best_substitution,bid,winnings\n");
+            for standing in standings {
+                output.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    standing.rank,
+                    csv_field(&standing.hand),
+                    csv_field(&standing.hand_type),
+                    csv_field(&standing.best_substitution),
+                    standing.bid,
+                    standing.winnings
+                ));
+            }
+        }
+        StandingsFormat::Json => {
+            let entries: Vec<String> = standings
+                .iter()
+                .map(|standing| {
+                    format!(
+                        "  {{\"rank\": {}, \"hand\": {}, \"hand_type\": {}, \"best_substitution\": {}, \"bid\": {}, \"winnings\": {}}}",
+                        standing.rank,
+                        json_string(&standing.hand),
+                        json_string(&standing.hand_type),
+                        json_string(&standing.best_substitution),
+                        standing.bid,
+                        standing.winnings
+                    )
+                })
+                .collect();
+
+            if entries.is_empty() {
+                output.push_str("[]\n");
+            } else {
+                output.push_str(&format!("[\n{}\n]\n", entries.join(",\n")));
+            }
+        }
+    }
+
+    output
+}
+
+// Fields with a separator, quote or line break are quoted, with quotes doubled.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut json = String::from("\"");
+    for character in string.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character.is_control() => {
+                json.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standings() -> Vec<Standing> {
+        vec![
+            Standing::new(
+                1,
+                String::from("32T3K"),
+                String::from("One pair"),
+                String::from("32T3K"),
+                765,
+            ),
+            Standing::new(
+                2,
+                String::from("KTJJT"),
+                String::from("Four of a kind"),
+                String::from("KTTTT"),
+                220,
+            ),
+        ]
+    }
+
+    #[test]
+    fn export_text() {
+        assert_eq!(
+            "  Rank | Hand   | Type            | Substitution |    Bid |   Winnings\n     \
+             1 | 32T3K  | One pair        | 32T3K        |    765 |        765\n     \
+             2 | KTJJT  | Four of a kind  | KTTTT        |    220 |        440\n",
+            export(&standings(), StandingsFormat::Text)
+        );
+    }
+
+    #[test]
+    fn export_csv() {
+        assert_eq!(
+            "rank,hand,hand_type,best_substitution,bid,winnings\n\
+             1,32T3K,One pair,32T3K,765,765\n\
+             2,KTJJT,Four of a kind,KTTTT,220,440\n",
+            export(&standings(), StandingsFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn export_json() {
+        assert_eq!(
+            "[\n  \
+             {\"rank\": 1, \"hand\": \"32T3K\", \"hand_type\": \"One pair\", \"best_substitution\": \"32T3K\", \"bid\": 765, \"winnings\": 765},\n  \
+             {\"rank\": 2, \"hand\": \"KTJJT\", \"hand_type\": \"Four of a kind\", \"best_substitution\": \"KTTTT\", \"bid\": 220, \"winnings\": 440}\n\
+             ]\n",
+            export(&standings(), StandingsFormat::Json)
+        );
+        assert_eq!("[]\n", export(&[], StandingsFormat::Json));
+    }
+
+    #[test]
+    fn export_escapes_labels_and_category_names() {
+        let standings = vec![Standing::new(
+            1,
+            String::from(r#"K"K"#),
+            String::from(r#"Pair, "lucky" \o/"#),
+            String::from(r#"K"K"#),
+            5,
+        )];
+
+        assert_eq!(
+            "rank,hand,hand_type,best_substitution,bid,winnings\n".to_string()
+                + r#"1,"K""K","Pair, ""lucky"" \o/","K""K",5,5"#
+                + "\n",
+            export(&standings, StandingsFormat::Csv)
+        );
+        assert_eq!(
+            "[\n  ".to_string()
+                + r#"{"rank": 1, "hand": "K\"K", "hand_type": "Pair, \"lucky\" \\o/", "best_substitution": "K\"K", "bid": 5, "winnings": 5}"#
+                + "\n]\n",
+            export(&standings, StandingsFormat::Json)
+        );
+        assert_eq!(r#""tab\tbell\u0007""#, json_string("tab\tbell\u{7}"));
+    }
+}
@@ -0,0 +1,118 @@
+#[macro_use]
+extern crate criterion;
+extern crate day_8;
+
+use criterion::Criterion;
+use day_8::DesertMap;
+use std::collections::HashMap;
+
+// Both lengths are prime, so AAA reaches the one node that can turn left to
+// ZZZ at the one left instruction only after millions of steps.
+const RING_LENGTH: usize = 9973;
+const INSTRUCTIONS_LENGTH: usize = 1009;
+
+fn node_name(index: usize) -> String {
+    if index == 0 {
+        String::from("AAA")
+    } else {
+        format!("N{:04}", index)
+    }
+}
+
+fn generate_map() -> String {
+    let mut instructions = String::from("L");
+    instructions.push_str(&"R".repeat(INSTRUCTIONS_LENGTH - 1));
+
+    let mut network: Vec<String> = (0..RING_LENGTH)
+        .map(|index| {
+            let next_node = node_name((index + 1) % RING_LENGTH);
+            let left_node = if index == RING_LENGTH / 2 {
+                String::from("ZZZ")
+            } else {
+                next_node.clone()
+            };
+            format!("{} = ({}, {})", node_name(index), left_node, next_node)
+        })
+        .collect();
+    network.push(String::from("ZZZ = (ZZZ, ZZZ)"));
+
+    format!("{}\n\n{}", instructions, network.join("\n"))
+}
+
+// The string keyed walk DesertMap used before node names were interned.
+struct StringDesertMap {
+    instructions: Vec<char>,
+    node_network: HashMap<String, (String, String)>,
+}
+
+impl StringDesertMap {
+    fn parse(map: &str) -> Self {
+        let mut lines = map.lines().filter(|line| !line.is_empty());
+        let instructions = lines.next().unwrap().chars().collect();
+        let node_network = lines
+            .map(|line| {
+                let (node, destinations) = line.split_once(" = ").unwrap();
+                let (left, right) = destinations
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .split_once(", ")
+                    .unwrap();
+                (node.to_string(), (left.to_string(), right.to_string()))
+            })
+            .collect();
+
+        StringDesertMap {
+            instructions,
+            node_network,
+        }
+    }
+
+    fn number_of_steps(&self, start_node: &str, end_node: &str) -> u64 {
+        let mut current_node: &str = start_node;
+        let mut steps_counter = 0_u64;
+
+        'traversal: loop {
+            for instruction in &self.instructions {
+                let destination = &self.node_network.get(current_node).unwrap();
+
+                current_node = if instruction == &'L' {
+                    &destination.0
+                } else {
+                    &destination.1
+                };
+
+                steps_counter += 1;
+                if current_node.cmp(end_node).is_eq() {
+                    break 'traversal;
+                }
+            }
+        }
+
+        steps_counter
+    }
+}
+
+fn desert_walk(c: &mut Criterion) {
+    let map = generate_map();
+    let string_desert_map = StringDesertMap::parse(&map);
+    let desert_map = DesertMap::parse(&map);
+    assert_eq!(
+        string_desert_map.number_of_steps("AAA", "ZZZ"),
+        desert_map.number_of_steps("AAA", "ZZZ").unwrap()
+    );
+
+    let mut group = c.benchmark_group("walk from AAA to ZZZ");
+    group.sample_size(10);
+
+    group.bench_function("string keyed network", |b| {
+        b.iter(|| string_desert_map.number_of_steps("AAA", "ZZZ"))
+    });
+
+    group.bench_function("interned adjacency", |b| {
+        b.iter(|| desert_map.number_of_steps("AAA", "ZZZ").unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, desert_walk);
+criterion_main!(benches);
@@ -11,15 +11,15 @@ fn main() -> std::io::Result<()> {
 
     desert_map_input_file.read_to_string(&mut desert_map_input)?;
     let desert_map = DesertMap::parse(&desert_map_input);
-    println!(
-        "Steps from 'AAA' to 'ZZZ': {}",
-        desert_map.number_of_steps("AAA", "ZZZ")
-    );
+    match desert_map.number_of_steps("AAA", "ZZZ") {
+        Ok(steps) => println!("Steps from 'AAA' to 'ZZZ': {}", steps),
+        Err(error) => eprintln!("Unable to count steps from 'AAA' to 'ZZZ': {}", error),
+    }
 
-    println!(
-        "Steps from '??A' to '??Z': {}",
-        desert_map.number_of_ghost_steps('A', 'Z')
-    );
+    match desert_map.number_of_ghost_steps('A', 'Z') {
+        Ok(steps) => println!("Steps from '??A' to '??Z': {}", steps),
+        Err(error) => eprintln!("Unable to count steps from '??A' to '??Z': {}", error),
+    }
 
     Ok(())
 }
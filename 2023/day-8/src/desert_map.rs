@@ -1,11 +1,63 @@
-use num::integer::lcm;
+use ghost_cycle::{first_common_hit, GhostCycle};
 use scanf::sscanf;
 use std::collections::HashMap;
+use std::fmt;
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum DesertMapError {
+    EmptyInstructions,
+    UnknownNode(String),
+    UnreachableNode {
+        start_node: String,
+        end_node: String,
+    },
+    UnreachableEnding {
+        start_node: String,
+        end_node_ending: char,
+    },
+    NoStartNodes(char),
+    GhostsNeverAligned,
+    GhostStepsOverflow,
+}
+
+impl fmt::Display for DesertMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DesertMapError::EmptyInstructions => write!(f, "there are no instructions"),
+            DesertMapError::UnknownNode(node) => write!(f, "node `{}` is not in the network", node),
+            DesertMapError::UnreachableNode {
+                start_node,
+                end_node,
+            } => write!(f, "`{}` can not be reached from `{}`", end_node, start_node),
+            DesertMapError::UnreachableEnding {
+                start_node,
+                end_node_ending,
+            } => write!(
+                f,
+                "no node ending with `{}` can be reached from `{}`",
+                end_node_ending, start_node
+            ),
+            DesertMapError::NoStartNodes(start_node_ending) => {
+                write!(f, "no node ends with `{}`", start_node_ending)
+            }
+            DesertMapError::GhostsNeverAligned => {
+                write!(f, "ghosts never stand on end nodes at the same time")
+            }
+            DesertMapError::GhostStepsOverflow => {
+                write!(f, "ghost step count does not fit into 64 bits")
+            }
+        }
+    }
+}
+
+// Nodes are numbered in the order they are defined, followed by the nodes
+// that are only ever named as a destination; those have no adjacency entry.
 #[derive(Debug)]
 pub struct DesertMap {
     instructions: Vec<char>,
-    node_network: HashMap<String, Destination>,
+    node_names: Vec<String>,
+    node_ids: HashMap<String, u32>,
+    adjacency: Vec<[u32; 2]>,
 }
 
 impl DesertMap {
@@ -13,118 +65,242 @@ impl DesertMap {
         let mut map_iter = map.trim().split('\n').filter(|line| !line.is_empty());
 
         let mut instructions: Vec<char> = Vec::new();
-        let direction = &map_iter.next().unwrap();
+        let direction = &map_iter.next().unwrap_or_default();
         direction
             .trim()
             .chars()
             .for_each(|character| instructions.push(character));
 
-        let mut node_network: HashMap<String, Destination> = HashMap::new();
-        map_iter.for_each(|line| {
-            let mut node: String = String::new();
-            let mut left: String = String::new();
-            let mut right: String = String::new();
+        let network: Vec<(String, String, String)> = map_iter
+            .map(|line| {
+                let mut node: String = String::new();
+                let mut left: String = String::new();
+                let mut right: String = String::new();
 
-            _ = sscanf!(line.trim(), "{} = ({}, {})", node, left, right);
-            node_network.insert(node, Destination { left, right });
-        });
+                _ = sscanf!(line.trim(), "{} = ({}, {})", node, left, right);
+                (node, left, right)
+            })
+            .collect();
 
-        DesertMap {
+        let mut desert_map = DesertMap {
             instructions,
-            node_network,
+            node_names: Vec::new(),
+            node_ids: HashMap::new(),
+            adjacency: Vec::new(),
+        };
+
+        for (node, _, _) in &network {
+            desert_map.intern(node);
+        }
+        // A node defined twice keeps its last destinations, like a map insert.
+        desert_map.adjacency = vec![[0, 0]; desert_map.node_names.len()];
+        for (node, left, right) in &network {
+            let destinations = [desert_map.intern(left), desert_map.intern(right)];
+            desert_map.adjacency[desert_map.node_ids[node] as usize] = destinations;
         }
+
+        desert_map
     }
 
-    pub fn number_of_steps(&self, start_node: &str, end_node: &str) -> u64 {
-        let mut current_node: &str = start_node;
+    fn intern(&mut self, node: &str) -> u32 {
+        if let Some(&node_id) = self.node_ids.get(node) {
+            return node_id;
+        }
+
+        let node_id = self.node_names.len() as u32;
+        self.node_names.push(node.to_string());
+        self.node_ids.insert(node.to_string(), node_id);
+        node_id
+    }
+
+    pub fn number_of_steps(&self, start_node: &str, end_node: &str) -> Result<u64, DesertMapError> {
+        if self.instructions.is_empty() {
+            return Err(DesertMapError::EmptyInstructions);
+        }
+
+        let directions = self.directions();
+        let mut current_node = self.node_id(start_node)?;
+        // An end node that is not in the network can still be missing; the
+        // walk then fails on the first node without destinations or repeats.
+        let end_node_id = self.node_ids.get(end_node).copied();
+
+        // There are only so many (node, instruction index) states and the
+        // walk from each of them is always the same, so walking longer than
+        // that means the end node is never reached.
+        let state_count = self.node_names.len() as u64 * directions.len() as u64;
         let mut steps_counter = 0_u64;
 
-        'traversal: loop {
-            for instruction in &self.instructions {
-                let destination = &self.node_network.get(current_node).unwrap();
+        loop {
+            if steps_counter >= state_count {
+                return Err(DesertMapError::UnreachableNode {
+                    start_node: start_node.to_string(),
+                    end_node: end_node.to_string(),
+                });
+            }
 
-                current_node = if instruction == &'L' || instruction == &'l' {
-                    &destination.left
-                } else {
-                    // instruction == &'R' || instruction == &'r'
-                    &destination.right
-                };
+            let direction = directions[steps_counter as usize % directions.len()];
+            current_node = self.next_node(current_node, direction)?;
+            steps_counter += 1;
+            if Some(current_node) == end_node_id {
+                return Ok(steps_counter);
+            }
+        }
+    }
 
-                steps_counter += 1;
-                if current_node.cmp(end_node).is_eq() {
-                    break 'traversal;
-                }
+    pub fn number_of_ghost_steps(
+        &self,
+        start_node_ending: char,
+        end_node_ending: char,
+    ) -> Result<u64, DesertMapError> {
+        if self.instructions.is_empty() {
+            return Err(DesertMapError::EmptyInstructions);
+        }
+
+        // Destination-only nodes are never walked from, so only defined nodes
+        // can be start nodes.
+        let mut start_nodes: Vec<&String> = self.node_names[..self.adjacency.len()]
+            .iter()
+            .filter(|node| node.ends_with(start_node_ending))
+            .collect();
+        if start_nodes.is_empty() {
+            return Err(DesertMapError::NoStartNodes(start_node_ending));
+        }
+        start_nodes.sort();
+
+        let end_nodes = self.nodes_ending_with(end_node_ending);
+        let mut ghost_cycles: Vec<GhostCycle> = Vec::new();
+        for start_node in start_nodes {
+            let ghost_cycle = self.ghost_cycle(self.node_ids[start_node], &end_nodes)?;
+            if !ghost_cycle.has_hits() {
+                return Err(DesertMapError::UnreachableEnding {
+                    start_node: start_node.to_string(),
+                    end_node_ending,
+                });
             }
+            ghost_cycles.push(ghost_cycle);
         }
 
-        steps_counter
+        first_common_hit(&ghost_cycles)?.ok_or(DesertMapError::GhostsNeverAligned)
     }
 
-    pub fn number_of_ghost_steps(&self, start_node_ending: char, end_node_ending: char) -> u64 {
-        let current_nodes: Vec<&str> = self
-            .node_network
-            .keys()
-            .filter(|key| key.ends_with(start_node_ending))
-            .map(|key| key.as_str())
-            .collect();
-        let mut step_counters: Vec<u64> = Vec::new();
-
-        for mut current_node in current_nodes {
-            let mut steps_counter = 0_u64;
-            'traversal: loop {
-                for instruction in &self.instructions {
-                    let destination = &self.node_network.get(current_node).unwrap();
-
-                    current_node = if instruction == &'L' || instruction == &'l' {
-                        &destination.left
-                    } else {
-                        // instruction == &'R' || instruction == &'r'
-                        &destination.right
-                    };
-
-                    steps_counter += 1;
-                    if current_node.ends_with(end_node_ending) {
-                        break 'traversal;
-                    }
-                }
+    // Walks from the start node until a (node, instruction index) state
+    // repeats, noting every step that lands on an end node on the way.
+    fn ghost_cycle(
+        &self,
+        start_node: u32,
+        end_nodes: &[bool],
+    ) -> Result<GhostCycle, DesertMapError> {
+        const NOT_VISITED: u64 = u64::MAX;
+
+        let directions = self.directions();
+        let mut visited_states: Vec<u64> =
+            vec![NOT_VISITED; self.node_names.len() * directions.len()];
+        let mut hits: Vec<u64> = Vec::new();
+        let mut current_node = start_node;
+        let mut steps_counter = 0_u64;
+
+        loop {
+            let instruction_index = steps_counter as usize % directions.len();
+            let state = current_node as usize * directions.len() + instruction_index;
+            if visited_states[state] != NOT_VISITED {
+                let cycle_start = visited_states[state];
+                return Ok(GhostCycle::new(
+                    cycle_start,
+                    steps_counter - cycle_start,
+                    hits,
+                ));
+            }
+            visited_states[state] = steps_counter;
+
+            if end_nodes[current_node as usize] {
+                hits.push(steps_counter);
             }
 
-            step_counters.push(steps_counter);
+            current_node = self.next_node(current_node, directions[instruction_index])?;
+            steps_counter += 1;
         }
+    }
+
+    // Instructions as indices into the adjacency entries.
+    fn directions(&self) -> Vec<usize> {
+        self.instructions
+            .iter()
+            .map(|&instruction| {
+                if instruction == 'L' || instruction == 'l' {
+                    0
+                } else {
+                    // instruction == 'R' || instruction == 'r'
+                    1
+                }
+            })
+            .collect()
+    }
 
-        DesertMap::lcm_extended(step_counters)
+    fn nodes_ending_with(&self, ending: char) -> Vec<bool> {
+        self.node_names
+            .iter()
+            .map(|node| node.ends_with(ending))
+            .collect()
     }
 
-    fn lcm_extended(numbers: Vec<u64>) -> u64 {
-        let mut numbers_iterator = numbers.iter();
-        let first_number = *numbers_iterator.next().unwrap();
-        numbers_iterator.fold(first_number, |last_lcm, step_counter| {
-            lcm(last_lcm, *step_counter)
-        })
+    fn node_id(&self, node: &str) -> Result<u32, DesertMapError> {
+        self.node_ids
+            .get(node)
+            .copied()
+            .ok_or_else(|| DesertMapError::UnknownNode(node.to_string()))
     }
-}
 
-#[derive(Debug, PartialEq)]
-struct Destination {
-    left: String,
-    right: String,
+    fn next_node(&self, node: u32, direction: usize) -> Result<u32, DesertMapError> {
+        self.adjacency
+            .get(node as usize)
+            .map(|destinations| destinations[direction])
+            .ok_or_else(|| DesertMapError::UnknownNode(self.node_names[node as usize].clone()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::XorShift;
+
+    #[derive(Debug, PartialEq)]
+    struct Destination {
+        left: String,
+        right: String,
+    }
+
+    fn node_network(desert_map: &DesertMap) -> HashMap<String, Destination> {
+        desert_map
+            .adjacency
+            .iter()
+            .enumerate()
+            .map(|(node, &[left, right])| {
+                (
+                    desert_map.node_names[node].clone(),
+                    Destination {
+                        left: desert_map.node_names[left as usize].clone(),
+                        right: desert_map.node_names[right as usize].clone(),
+                    },
+                )
+            })
+            .collect()
+    }
 
     fn asset_desert_map_steps_to_end(expected_number_of_steps: u64, desert_map: &str) {
         assert_eq!(
             expected_number_of_steps,
-            DesertMap::parse(desert_map).number_of_steps("AAA", "ZZZ")
+            DesertMap::parse(desert_map)
+                .number_of_steps("AAA", "ZZZ")
+                .unwrap()
         );
     }
 
     fn asset_desert_map_ghost_steps_to_end(expected_number_of_steps: u64, desert_map: &str) {
         assert_eq!(
             expected_number_of_steps,
-            DesertMap::parse(desert_map).number_of_ghost_steps('A', 'Z')
+            DesertMap::parse(desert_map)
+                .number_of_ghost_steps('A', 'Z')
+                .unwrap()
         );
     }
 
@@ -152,7 +328,7 @@ mod tests {
                     right: "CCC".to_string()
                 }
             )]),
-            DesertMap::parse("LRL\n\nAAA = (BBB, CCC)").node_network
+            node_network(&DesertMap::parse("LRL\n\nAAA = (BBB, CCC)"))
         );
     }
 
@@ -175,7 +351,9 @@ mod tests {
                     }
                 )
             ]),
-            DesertMap::parse("LRL\n\nAAA = (BBB, CCC)\nBBB = (CCC, ZZZ)").node_network
+            node_network(&DesertMap::parse(
+                "LRL\n\nAAA = (BBB, CCC)\nBBB = (CCC, ZZZ)"
+            ))
         );
     }
 
@@ -209,4 +387,190 @@ mod tests {
             "LR\n\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)",
         );
     }
+
+    #[test]
+    fn desert_map_ghost_steps_with_offset() {
+        // 11A reaches 11Z after 2 steps and then every 3 steps, 22A after 1
+        // step and then every 2 steps, so the cycle lengths' LCM (6) is wrong.
+        asset_desert_map_ghost_steps_to_end(
+            5,
+            "L\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (11C, 11C)\n11C = (11D, 11D)\n11D = (11Z, 11Z)\n22A = (22Z, 22Z)\n22Z = (22B, 22B)\n22B = (22Z, 22Z)",
+        );
+    }
+
+    #[test]
+    fn desert_map_ghost_steps_with_multiple_end_nodes_in_cycle() {
+        // 11A is on an end node at steps 2, 3, 5, 6, 8, ... and 22A at steps
+        // 4, 8, 12, ...
+        asset_desert_map_ghost_steps_to_end(
+            8,
+            "L\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (12Z, 12Z)\n12Z = (11B, 11B)\n22A = (22B, 22B)\n22B = (22C, 22C)\n22C = (22D, 22D)\n22D = (22Z, 22Z)\n22Z = (22B, 22B)",
+        );
+    }
+
+    fn brute_force_ghost_steps(desert_map: &DesertMap, step_limit: u64) -> Option<u64> {
+        let mut current_nodes: Vec<&str> = desert_map
+            .node_names
+            .iter()
+            .filter(|node| node.ends_with('A'))
+            .map(|node| node.as_str())
+            .collect();
+
+        for step in 1..=step_limit {
+            let instruction =
+                desert_map.instructions[(step - 1) as usize % desert_map.instructions.len()];
+            for current_node in current_nodes.iter_mut() {
+                let [left, right] =
+                    desert_map.adjacency[desert_map.node_ids[*current_node] as usize];
+                let next_node = if instruction == 'L' { left } else { right };
+                *current_node = &desert_map.node_names[next_node as usize];
+            }
+
+            if current_nodes.iter().all(|node| node.ends_with('Z')) {
+                return Some(step);
+            }
+        }
+
+        None
+    }
+
+    #[test]
+    fn desert_map_ghost_steps_match_brute_force() {
+        const STEP_LIMIT: u64 = 5_000;
+        let mut random = XorShift::default();
+
+        for _ in 0..300 {
+            let node_count = random.next(8) + 2;
+            let node_name = |index: u64, random: &mut XorShift| {
+                let ending = match (index, random.next(3)) {
+                    (0, _) | (_, 0) => 'A',
+                    (_, 1) => 'Z',
+                    _ => 'X',
+                };
+                format!("{:02}{}", index, ending)
+            };
+            let node_names: Vec<String> = (0..node_count)
+                .map(|index| node_name(index, &mut random))
+                .collect();
+
+            let instructions: String = (0..random.next(4) + 1)
+                .map(|_| if random.next(2) == 0 { 'L' } else { 'R' })
+                .collect();
+            let network: Vec<String> = node_names
+                .iter()
+                .map(|node| {
+                    format!(
+                        "{} = ({}, {})",
+                        node,
+                        node_names[random.next(node_count) as usize],
+                        node_names[random.next(node_count) as usize]
+                    )
+                })
+                .collect();
+            let desert_map =
+                DesertMap::parse(&format!("{}\n\n{}", instructions, network.join("\n")));
+
+            let end_nodes = desert_map.nodes_ending_with('Z');
+            let ghost_cycles: Vec<GhostCycle> = desert_map
+                .node_names
+                .iter()
+                .filter(|node| node.ends_with('A'))
+                .map(|start_node| {
+                    desert_map
+                        .ghost_cycle(desert_map.node_ids[start_node], &end_nodes)
+                        .unwrap()
+                })
+                .collect();
+            let common_hit = first_common_hit(&ghost_cycles).unwrap();
+
+            match brute_force_ghost_steps(&desert_map, STEP_LIMIT) {
+                Some(steps) => assert_eq!(Some(steps), common_hit),
+                None => assert!(common_hit.is_none_or(|steps| steps > STEP_LIMIT)),
+            }
+        }
+    }
+
+    #[test]
+    fn desert_map_empty_instructions() {
+        let desert_map = DesertMap::parse("");
+        assert_eq!(
+            Err(DesertMapError::EmptyInstructions),
+            desert_map.number_of_steps("AAA", "ZZZ")
+        );
+        assert_eq!(
+            Err(DesertMapError::EmptyInstructions),
+            desert_map.number_of_ghost_steps('A', 'Z')
+        );
+    }
+
+    #[test]
+    fn desert_map_unknown_node() {
+        let desert_map = DesertMap::parse("L\n\nAAA = (BBB, BBB)\n11A = (11B, 11B)");
+        assert_eq!(
+            Err(DesertMapError::UnknownNode(String::from("BBB"))),
+            desert_map.number_of_steps("AAA", "ZZZ")
+        );
+        assert_eq!(
+            Err(DesertMapError::UnknownNode(String::from("CCC"))),
+            desert_map.number_of_steps("CCC", "ZZZ")
+        );
+        assert_eq!(
+            Err(DesertMapError::UnknownNode(String::from("11B"))),
+            desert_map.number_of_ghost_steps('A', 'Z')
+        );
+    }
+
+    #[test]
+    fn desert_map_destination_only_nodes_are_not_start_nodes() {
+        let desert_map = DesertMap::parse("L\n\n11A = (11Z, XXA)\n11Z = (11Z, 11Z)");
+        assert_eq!(Ok(1), desert_map.number_of_ghost_steps('A', 'Z'));
+
+        let desert_map = DesertMap::parse("L\n\n11B = (XXA, XXA)");
+        assert_eq!(
+            Err(DesertMapError::NoStartNodes('A')),
+            desert_map.number_of_ghost_steps('A', 'Z')
+        );
+    }
+
+    #[test]
+    fn desert_map_unreachable_end_node() {
+        let desert_map =
+            DesertMap::parse("LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)");
+        assert_eq!(
+            Err(DesertMapError::UnreachableNode {
+                start_node: String::from("AAA"),
+                end_node: String::from("ZZZ"),
+            }),
+            desert_map.number_of_steps("AAA", "ZZZ")
+        );
+        assert_eq!(
+            Err(DesertMapError::UnreachableEnding {
+                start_node: String::from("AAA"),
+                end_node_ending: 'Z',
+            }),
+            desert_map.number_of_ghost_steps('A', 'Z')
+        );
+    }
+
+    #[test]
+    fn desert_map_ghosts_never_aligned() {
+        let desert_map = DesertMap::parse(
+            "L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22C, 22C)\n22C = (22A, 22A)",
+        );
+        assert_eq!(
+            Err(DesertMapError::GhostsNeverAligned),
+            desert_map.number_of_ghost_steps('A', 'Z')
+        );
+        assert_eq!(
+            Err(DesertMapError::NoStartNodes('Q')),
+            desert_map.number_of_ghost_steps('Q', 'Z')
+        );
+    }
+
+    #[test]
+    fn desert_map_interns_destination_only_nodes_last() {
+        let desert_map = DesertMap::parse("L\n\nBBB = (CCC, AAA)\nAAA = (BBB, BBB)");
+        assert_eq!(vec!["BBB", "AAA", "CCC"], desert_map.node_names);
+        assert_eq!(vec![[2, 1], [0, 0]], desert_map.adjacency);
+    }
 }
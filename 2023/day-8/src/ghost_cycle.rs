@@ -0,0 +1,255 @@
+use desert_map::DesertMapError;
+use num::integer::Integer;
+use std::convert::TryFrom;
+
+// Every ghost walks a fixed path: after cycle_start steps it reaches a
+// (node, instruction index) state it has been in before, and from then on it
+// repeats the same cycle_length steps forever. Hits are the step counts at
+// which it stands on an end node, step 0 being the start node.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct GhostCycle {
+    cycle_start: u64,
+    cycle_length: u64,
+    // Hits before cycle_start, they never happen again.
+    prefix_hits: Vec<u64>,
+    // Hits within the first cycle, each recurs every cycle_length steps.
+    cycle_hits: Vec<u64>,
+}
+
+impl GhostCycle {
+    // Hits have to be in ascending order.
+    pub(crate) fn new(cycle_start: u64, cycle_length: u64, hits: Vec<u64>) -> Self {
+        let (prefix_hits, cycle_hits) = hits.into_iter().partition(|&hit| hit < cycle_start);
+
+        GhostCycle {
+            cycle_start,
+            cycle_length,
+            prefix_hits,
+            cycle_hits,
+        }
+    }
+
+    pub(crate) fn has_hits(&self) -> bool {
+        !self.prefix_hits.is_empty() || !self.cycle_hits.is_empty()
+    }
+
+    fn is_hit(&self, step: u64) -> bool {
+        if step < self.cycle_start {
+            self.prefix_hits.binary_search(&step).is_ok()
+        } else {
+            let offset = (step - self.cycle_start) % self.cycle_length;
+            self.cycle_hits
+                .binary_search(&(self.cycle_start + offset))
+                .is_ok()
+        }
+    }
+
+    // The LCM of the cycle lengths is only the answer when every ghost hits
+    // an end node exactly once per cycle, at a multiple of its cycle length.
+    fn hits_only_at_cycle_length(&self) -> bool {
+        self.cycle_hits == [self.cycle_length]
+    }
+}
+
+// Returns the first step, after at least one, at which all ghosts stand on an
+// end node at the same time, or None when that never happens.
+pub(crate) fn first_common_hit(ghost_cycles: &[GhostCycle]) -> Result<Option<u64>, DesertMapError> {
+    // Before the last ghost enters its cycle only its prefix hits can be
+    // common hits, so those are checked one by one.
+    let Some(last_to_cycle) = ghost_cycles
+        .iter()
+        .max_by_key(|ghost_cycle| ghost_cycle.cycle_start)
+    else {
+        return Ok(None);
+    };
+    if let Some(step) = last_to_cycle
+        .prefix_hits
+        .iter()
+        .copied()
+        .filter(|&step| step > 0)
+        .find(|&step| {
+            ghost_cycles
+                .iter()
+                .all(|ghost_cycle| ghost_cycle.is_hit(step))
+        })
+    {
+        return Ok(Some(step));
+    }
+
+    if ghost_cycles
+        .iter()
+        .all(GhostCycle::hits_only_at_cycle_length)
+    {
+        return ghost_cycles
+            .iter()
+            .try_fold(1_u64, |cycle_lcm, ghost_cycle| {
+                (cycle_lcm / cycle_lcm.gcd(&ghost_cycle.cycle_length))
+                    .checked_mul(ghost_cycle.cycle_length)
+            })
+            .map(Some)
+            .ok_or(DesertMapError::GhostStepsOverflow);
+    }
+
+    // Otherwise every ghost is in its cycle, where each cycle hit is a
+    // congruence; every combination of them is solved with the CRT. The
+    // combined congruences all share the LCM of the cycle lengths so far as
+    // their modulus, which has to stay within i128 for the CRT.
+    let mut congruences: Vec<(u128, u128)> = vec![(0, 1)];
+    let mut cycle_lcm: u128 = 1;
+    for ghost_cycle in ghost_cycles {
+        let cycle_length = ghost_cycle.cycle_length as u128;
+        cycle_lcm = (cycle_lcm / cycle_lcm.gcd(&cycle_length))
+            .checked_mul(cycle_length)
+            .filter(|&cycle_lcm| cycle_lcm <= i128::MAX as u128)
+            .ok_or(DesertMapError::GhostStepsOverflow)?;
+
+        let mut combined: Vec<(u128, u128)> = Vec::new();
+        for &(residue, modulus) in &congruences {
+            for &hit in &ghost_cycle.cycle_hits {
+                let congruence = ((hit % ghost_cycle.cycle_length) as u128, cycle_length);
+                if let Some(congruence) = solve_congruences((residue, modulus), congruence) {
+                    if !combined.contains(&congruence) {
+                        combined.push(congruence);
+                    }
+                }
+            }
+        }
+        congruences = combined;
+    }
+
+    let lower_bound = last_to_cycle.cycle_start.max(1) as u128;
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= lower_bound {
+                residue
+            } else {
+                residue + (lower_bound - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
+        .map(|step| u64::try_from(step).map_err(|_| DesertMapError::GhostStepsOverflow))
+        .transpose()
+}
+
+// Combines x = a (mod m) and x = b (mod n) into a single congruence modulo
+// lcm(m, n), the moduli do not have to be coprime.
+fn solve_congruences((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    let extended_gcd = (m as i128).extended_gcd(&(n as i128));
+    let gcd = extended_gcd.gcd as u128;
+    let difference = (b as i128 - a as i128).rem_euclid(n as i128) as u128;
+    if !difference.is_multiple_of(gcd) {
+        return None;
+    }
+
+    // m * k = b - a (mod n) is solved by k = (b - a) / gcd * x (mod n / gcd).
+    let reduced_modulus = n / gcd;
+    let inverse = extended_gcd.x.rem_euclid(reduced_modulus as i128) as u128;
+    let k = (difference / gcd % reduced_modulus) * inverse % reduced_modulus;
+
+    let modulus = m / gcd * n;
+    Some(((a + m * k) % modulus, modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_congruences_coprime_and_not() {
+        assert_eq!(Some((8, 15)), solve_congruences((2, 3), (3, 5)));
+        assert_eq!(Some((10, 12)), solve_congruences((2, 4), (4, 6)));
+        assert_eq!(None, solve_congruences((1, 4), (2, 6)));
+        assert_eq!(Some((3, 7)), solve_congruences((0, 1), (3, 7)));
+    }
+
+    #[test]
+    fn first_common_hit_uses_lcm_when_verified() {
+        assert_eq!(
+            Ok(Some(12)),
+            first_common_hit(&[
+                GhostCycle::new(1, 4, vec![4]),
+                GhostCycle::new(2, 6, vec![6]),
+            ])
+        );
+    }
+
+    #[test]
+    fn first_common_hit_with_offsets() {
+        // Hits at 3, 8, 13, ... and at 1, 4, 7, 10, 13, ...
+        assert_eq!(
+            Ok(Some(13)),
+            first_common_hit(&[
+                GhostCycle::new(2, 5, vec![3]),
+                GhostCycle::new(0, 3, vec![1]),
+            ])
+        );
+    }
+
+    #[test]
+    fn first_common_hit_with_multiple_hits_per_cycle() {
+        // Hits at 2, 3, 6, 7, 10, 11, ... and at 5, 11, 17, ...
+        assert_eq!(
+            Ok(Some(11)),
+            first_common_hit(&[
+                GhostCycle::new(0, 4, vec![2, 3]),
+                GhostCycle::new(0, 6, vec![5]),
+            ])
+        );
+    }
+
+    #[test]
+    fn first_common_hit_before_cycles() {
+        assert_eq!(
+            Ok(Some(2)),
+            first_common_hit(&[
+                GhostCycle::new(5, 3, vec![2, 6]),
+                GhostCycle::new(1, 2, vec![2]),
+            ])
+        );
+    }
+
+    #[test]
+    fn first_common_hit_never() {
+        assert_eq!(
+            Ok(None),
+            first_common_hit(&[
+                GhostCycle::new(0, 2, vec![1]),
+                GhostCycle::new(0, 4, vec![2]),
+            ])
+        );
+        assert_eq!(Ok(None), first_common_hit(&[GhostCycle::new(3, 2, vec![])]));
+        assert_eq!(Ok(None), first_common_hit(&[]));
+    }
+
+    #[test]
+    fn first_common_hit_beyond_64_bits() {
+        // The cycle lengths are coprime and their product does not fit into
+        // 64 bits, so neither does their LCM nor hits at 1 and 3 that only
+        // align at 18446744206853538033.
+        const FIRST_CYCLE_LENGTH: u64 = (1 << 32) + 15;
+        const SECOND_CYCLE_LENGTH: u64 = (1 << 32) + 17;
+
+        assert_eq!(
+            Err(DesertMapError::GhostStepsOverflow),
+            first_common_hit(&[
+                GhostCycle::new(0, FIRST_CYCLE_LENGTH, vec![FIRST_CYCLE_LENGTH]),
+                GhostCycle::new(0, SECOND_CYCLE_LENGTH, vec![SECOND_CYCLE_LENGTH]),
+            ])
+        );
+        assert_eq!(
+            Err(DesertMapError::GhostStepsOverflow),
+            first_common_hit(&[
+                GhostCycle::new(0, FIRST_CYCLE_LENGTH, vec![1]),
+                GhostCycle::new(0, SECOND_CYCLE_LENGTH, vec![3]),
+            ])
+        );
+        assert_eq!(
+            Ok(Some(1)),
+            first_common_hit(&[
+                GhostCycle::new(0, FIRST_CYCLE_LENGTH, vec![1]),
+                GhostCycle::new(0, SECOND_CYCLE_LENGTH, vec![1, 3]),
+            ])
+        );
+    }
+}
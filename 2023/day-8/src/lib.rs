@@ -1,4 +1,7 @@
 extern crate num;
 extern crate scanf;
+#[cfg(test)]
+extern crate test_support;
 mod desert_map;
-pub use desert_map::DesertMap;
+mod ghost_cycle;
+pub use desert_map::{DesertMap, DesertMapError};
@@ -0,0 +1,21 @@
+// Fixtures shared by the tests and benches of the daily puzzle crates.
+
+// Deterministic xorshift generator for randomised tests and generated bench
+// input, so that a failing case always reproduces.
+pub struct XorShift(u64);
+
+impl Default for XorShift {
+    fn default() -> Self {
+        XorShift(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl XorShift {
+    // Returns a value below the bound.
+    pub fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}